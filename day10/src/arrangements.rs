@@ -0,0 +1,102 @@
+use num_bigint::BigUint;
+use num_traits::{CheckedAdd, One, Zero};
+
+// the socket followed by the adapters in ascending order
+pub fn joltages(adapters: &[usize]) -> Vec<usize> {
+    let mut joltages = Vec::with_capacity(adapters.len() + 1);
+    joltages.push(0);
+    joltages.extend_from_slice(adapters);
+    joltages.sort_unstable();
+    joltages
+}
+
+// indices of the joltages that can plug into joltages[idx], equal ratings
+// can't follow each other
+pub fn predecessors(
+    joltages: &[usize],
+    idx: usize,
+    max_step: usize,
+) -> impl Iterator<Item = usize> + Clone + '_ {
+    let joltage = joltages[idx];
+    (0..idx)
+        .rev()
+        .take_while(move |&from| joltage - joltages[from] <= max_step)
+        .filter(move |&from| joltages[from] < joltage)
+}
+
+// indices a chain can end on, the device only fits the largest adapters
+pub fn ends(joltages: &[usize]) -> impl Iterator<Item = usize> + Clone + '_ {
+    let max = *joltages.last().unwrap();
+    (0..joltages.len())
+        .rev()
+        .take_while(move |&idx| joltages[idx] == max)
+}
+
+// ways[i] is the number of chains from the socket ending at joltages[i],
+// None when a count overflows T. panics on a max step of 0, where no adapter
+// could follow another
+pub fn ways<T>(joltages: &[usize], max_step: usize) -> Option<Vec<T>>
+where
+    T: Clone + Zero + One + CheckedAdd,
+{
+    assert!(max_step > 0, "max step must be positive");
+    let mut ways: Vec<T> = vec![T::one()];
+    for idx in 1..joltages.len() {
+        let mut total = T::zero();
+        for from in predecessors(joltages, idx, max_step) {
+            total = total.checked_add(&ways[from])?;
+        }
+        ways.push(total);
+    }
+    Some(ways)
+}
+
+pub fn count_arrangements<T>(adapters: &[usize], max_step: usize) -> Option<T>
+where
+    T: Clone + Zero + One + CheckedAdd,
+{
+    let joltages = joltages(adapters);
+    let ways = ways::<T>(&joltages, max_step)?;
+    let total = ends(&joltages).try_fold(T::zero(), |total, idx| total.checked_add(&ways[idx]));
+    total
+}
+
+// u64 arithmetic unless the count doesn't fit
+pub fn arrangements(adapters: &[usize], max_step: usize) -> BigUint {
+    count_arrangements::<u64>(adapters, max_step)
+        .map(BigUint::from)
+        .unwrap_or_else(|| {
+            count_arrangements(adapters, max_step).expect("big integers don't overflow")
+        })
+}
+
+#[test]
+fn arrangements_test() {
+    let small = vec![16, 10, 15, 5, 1, 11, 7, 19, 6, 12, 4];
+    assert_eq!(count_arrangements::<u64>(&small, 3), Some(8));
+    let large = vec![
+        28, 33, 18, 42, 31, 14, 46, 20, 48, 47, 24, 23, 49, 45, 19, 38, 39, 11, 1, 32, 25, 35, 8,
+        17, 7, 9, 4, 2, 34, 10, 3,
+    ];
+    assert_eq!(count_arrangements::<u64>(&large, 3), Some(19208));
+    assert_eq!(count_arrangements::<u64>(&[2, 4], 3), Some(1));
+    assert_eq!(count_arrangements::<u64>(&[1, 2, 3], 3), Some(4));
+    assert_eq!(count_arrangements::<u64>(&[1, 2, 3], 1), Some(1));
+    assert_eq!(count_arrangements::<u64>(&[1, 1, 2], 3), Some(3));
+    assert_eq!(count_arrangements::<u64>(&[1, 2, 2], 3), Some(4));
+    assert_eq!(count_arrangements::<u64>(&[4], 3), Some(0));
+    assert_eq!(count_arrangements::<u64>(&[], 3), Some(1));
+
+    let long: Vec<usize> = (1..=200).collect();
+    assert_eq!(count_arrangements::<u64>(&long, 3), None);
+    assert_eq!(
+        arrangements(&long, 3).to_string(),
+        "52622583840983769603765180599790256716084480555530641"
+    );
+}
+
+#[test]
+#[should_panic(expected = "max step must be positive")]
+fn zero_step_test() {
+    count_arrangements::<u64>(&[1, 2], 0);
+}
@@ -0,0 +1,213 @@
+use num_bigint::{BigUint, RandBigInt};
+use num_traits::Zero;
+use rand::Rng;
+
+use crate::arrangements::{ends, joltages, predecessors, ways};
+
+// adapter joltages in the order they're plugged, socket and device left out.
+// adapters with equal ratings are interchangeable, so their chains look the same
+pub type Chain = Vec<usize>;
+
+// depth first over the adapters, one chain per `next`. like the counting in
+// arrangements, it panics on a max step of 0
+pub struct Chains {
+    joltages: Vec<usize>,
+    max_step: usize,
+    max: usize,
+    // (joltage index, next index to try after it)
+    path: Vec<(usize, usize)>,
+    // with no adapters above 0 the socket plugs straight into the device
+    empty_chain: bool,
+}
+
+pub fn chains(adapters: &[usize], max_step: usize) -> Chains {
+    assert!(max_step > 0, "max step must be positive");
+    let joltages = joltages(adapters);
+    let max = *joltages.last().unwrap();
+    Chains {
+        max,
+        empty_chain: max == 0,
+        path: if max == 0 { vec![] } else { vec![(0, 1)] },
+        joltages,
+        max_step,
+    }
+}
+
+impl Iterator for Chains {
+    type Item = Chain;
+
+    fn next(&mut self) -> Option<Chain> {
+        if self.empty_chain {
+            self.empty_chain = false;
+            return Some(vec![]);
+        }
+        loop {
+            let (node, candidate) = *self.path.last()?;
+            let in_reach = self
+                .joltages
+                .get(candidate)
+                .is_some_and(|&next| next <= self.joltages[node] + self.max_step);
+            if !in_reach {
+                self.path.pop();
+                continue;
+            }
+            self.path.last_mut().unwrap().1 += 1;
+            if self.joltages[candidate] == self.joltages[node] {
+                continue;
+            }
+            self.path.push((candidate, candidate + 1));
+            if self.joltages[candidate] == self.max {
+                let chain = self.path[1..]
+                    .iter()
+                    .map(|&(idx, _)| self.joltages[idx])
+                    .collect();
+                // nothing can follow the largest adapter
+                self.path.pop();
+                return Some(chain);
+            }
+        }
+    }
+}
+
+// fewest or most adapters, `better` decides if a length beats the current best
+fn extreme_chain(
+    adapters: &[usize],
+    max_step: usize,
+    better: fn(usize, usize) -> bool,
+) -> Option<Chain> {
+    assert!(max_step > 0, "max step must be positive");
+    let joltages = joltages(adapters);
+    // (chain length, previous index) of the best chain ending at each joltage
+    let mut best: Vec<Option<(usize, usize)>> = vec![Some((0, 0))];
+    for idx in 1..joltages.len() {
+        let mut best_here: Option<(usize, usize)> = None;
+        for from in predecessors(&joltages, idx, max_step) {
+            if let Some((len, _)) = best[from] {
+                if best_here.is_none_or(|(best_len, _)| better(len + 1, best_len)) {
+                    best_here = Some((len + 1, from));
+                }
+            }
+        }
+        best.push(best_here);
+    }
+    let mut idx = ends(&joltages)
+        .filter_map(|idx| best[idx].map(|(len, _)| (len, idx)))
+        .fold(None, |acc: Option<(usize, usize)>, (len, idx)| match acc {
+            Some((best_len, _)) if !better(len, best_len) => acc,
+            _ => Some((len, idx)),
+        })?
+        .1;
+    let mut chain = vec![];
+    while idx != 0 {
+        chain.push(joltages[idx]);
+        idx = best[idx].unwrap().1;
+    }
+    chain.reverse();
+    Some(chain)
+}
+
+pub fn shortest_chain(adapters: &[usize], max_step: usize) -> Option<Chain> {
+    extreme_chain(adapters, max_step, |len, best| len < best)
+}
+
+pub fn longest_chain(adapters: &[usize], max_step: usize) -> Option<Chain> {
+    extreme_chain(adapters, max_step, |len, best| len > best)
+}
+
+// picks one of `candidates` with probability proportional to its count
+fn weighted_pick(
+    candidates: impl Iterator<Item = usize> + Clone,
+    counts: &[BigUint],
+    rng: &mut impl Rng,
+) -> Option<usize> {
+    let total: BigUint = candidates.clone().map(|idx| &counts[idx]).sum();
+    if total.is_zero() {
+        return None;
+    }
+    let mut pick = rng.gen_biguint_below(&total);
+    for idx in candidates {
+        if pick < counts[idx] {
+            return Some(idx);
+        }
+        pick -= &counts[idx];
+    }
+    unreachable!("pick is below the total")
+}
+
+// walks back from the device choosing each previous adapter in proportion to
+// the chains ending on it, so every chain is equally likely
+pub fn random_chain(adapters: &[usize], max_step: usize, rng: &mut impl Rng) -> Option<Chain> {
+    let joltages = joltages(adapters);
+    let counts = ways::<BigUint>(&joltages, max_step).expect("big integers don't overflow");
+    let mut idx = weighted_pick(ends(&joltages), &counts, rng)?;
+    let mut chain = vec![];
+    while idx != 0 {
+        chain.push(joltages[idx]);
+        idx = weighted_pick(predecessors(&joltages, idx, max_step), &counts, rng)
+            .expect("a counted chain has a predecessor");
+    }
+    chain.reverse();
+    Some(chain)
+}
+
+#[test]
+fn chains_test() {
+    use crate::arrangements::count_arrangements;
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::collections::HashMap;
+
+    let small = vec![16, 10, 15, 5, 1, 11, 7, 19, 6, 12, 4];
+    let all: Vec<Chain> = chains(&small, 3).collect();
+    assert_eq!(all.len(), 8);
+    assert!(all.iter().all(|chain| {
+        let mut previous = 0;
+        chain.iter().all(|&joltage| {
+            let fits = joltage > previous && joltage - previous <= 3;
+            previous = joltage;
+            fits
+        }) && previous == 19
+    }));
+    let shortest = shortest_chain(&small, 3).unwrap();
+    let longest = longest_chain(&small, 3).unwrap();
+    assert_eq!(shortest, vec![1, 4, 7, 10, 12, 15, 16, 19]);
+    assert_eq!(Some(shortest.len()), all.iter().map(Vec::len).min());
+    assert_eq!(longest, vec![1, 4, 5, 6, 7, 10, 11, 12, 15, 16, 19]);
+
+    // brute force agrees with the counting
+    for adapters in [
+        vec![1, 2, 2, 3, 5],
+        vec![3, 1, 4, 1, 5, 9, 2, 6],
+        vec![],
+        vec![0],
+        vec![0, 0],
+    ]
+    .iter()
+    {
+        for max_step in 1..=4 {
+            assert_eq!(
+                Some(chains(adapters, max_step).count() as u64),
+                count_arrangements::<u64>(adapters, max_step)
+            );
+        }
+    }
+    assert_eq!(chains(&[4], 3).next(), None);
+    assert_eq!(shortest_chain(&[4], 3), None);
+
+    let mut rng = StdRng::seed_from_u64(2020);
+    let mut seen: HashMap<Chain, usize> = HashMap::new();
+    for _ in 0..800 {
+        *seen
+            .entry(random_chain(&small, 3, &mut rng).unwrap())
+            .or_default() += 1;
+    }
+    assert_eq!(seen.len(), 8);
+    assert!(seen.keys().all(|chain| all.contains(chain)));
+    assert!(seen.values().all(|&count| count > 50 && count < 150));
+    assert_eq!(random_chain(&[4], 3, &mut rng), None);
+}
+
+#[test]
+#[should_panic(expected = "max step must be positive")]
+fn zero_step_test() {
+    chains(&[1, 2], 0);
+}
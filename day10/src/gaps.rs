@@ -0,0 +1,97 @@
+use std::{collections::BTreeMap, fmt};
+
+use itertools::Itertools;
+
+use crate::arrangements::joltages;
+
+// a step in the chain using every adapter that no adapter accepts
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Violation {
+    pub from: usize,
+    pub to: usize,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.from == self.to {
+            write!(f, "two adapters rated {}", self.from)
+        } else {
+            write!(
+                f,
+                "gap of {} between {} and {}",
+                self.to - self.from,
+                self.from,
+                self.to
+            )
+        }
+    }
+}
+
+// socket, every adapter and the device in ascending order, as consecutive pairs.
+// with a max step of 0 the device would sit on the largest adapter and no gap
+// could ever be bridged
+fn steps(adapters: &[usize], max_step: usize) -> impl Iterator<Item = (usize, usize)> {
+    assert!(max_step > 0, "max step must be positive");
+    let mut joltages = joltages(adapters);
+    let device = joltages.last().unwrap() + max_step;
+    joltages.push(device);
+    joltages.into_iter().tuple_windows()
+}
+
+pub fn histogram(adapters: &[usize], max_step: usize) -> BTreeMap<usize, usize> {
+    let mut histogram = BTreeMap::new();
+    for (from, to) in steps(adapters, max_step) {
+        *histogram.entry(to - from).or_default() += 1;
+    }
+    histogram
+}
+
+// why every adapter can't be used at once, duplicates or gaps over `max_step`
+pub fn violations(adapters: &[usize], max_step: usize) -> Vec<Violation> {
+    steps(adapters, max_step)
+        .filter(|&(from, to)| from == to || to - from > max_step)
+        .map(|(from, to)| Violation { from, to })
+        .collect()
+}
+
+// the fewest ratings to add so some chain reaches the device. gaps are
+// independent, and each one is bridged greedily by stepping `max_step` at a time
+pub fn missing_adapters(adapters: &[usize], max_step: usize) -> Vec<usize> {
+    steps(adapters, max_step)
+        .flat_map(|(from, to)| {
+            (1..)
+                .map(move |n| from + n * max_step)
+                .take_while(move |&rating| rating < to)
+        })
+        .collect()
+}
+
+#[test]
+fn gaps_test() {
+    let small = vec![16, 10, 15, 5, 1, 11, 7, 19, 6, 12, 4];
+    assert_eq!(
+        histogram(&small, 3).into_iter().collect::<Vec<_>>(),
+        vec![(1, 7), (3, 5)]
+    );
+    assert!(violations(&small, 3).is_empty());
+    assert!(missing_adapters(&small, 3).is_empty());
+
+    let broken = vec![1, 5, 6, 6];
+    assert_eq!(
+        violations(&broken, 3),
+        vec![Violation { from: 1, to: 5 }, Violation { from: 6, to: 6 }]
+    );
+    assert_eq!(missing_adapters(&broken, 3), vec![4]);
+    assert_eq!(missing_adapters(&[10], 3), vec![3, 6, 9]);
+    assert_eq!(missing_adapters(&[10, 20], 5), vec![5, 15]);
+    assert_eq!(
+        Violation { from: 1, to: 5 }.to_string(),
+        "gap of 4 between 1 and 5"
+    );
+}
+
+#[test]
+#[should_panic(expected = "max step must be positive")]
+fn zero_step_test() {
+    missing_adapters(&[1, 5], 0);
+}
@@ -1,40 +1,25 @@
-use itertools::Itertools;
-use std::{fs::read_to_string, iter::once};
+use arrangements::arrangements;
+use chains::{chains, longest_chain, random_chain, shortest_chain};
+use gaps::{histogram, missing_adapters, violations, Violation};
+use num_bigint::BigUint;
+use std::{env, fs::read_to_string};
 
-fn get_gaps(adapters: &Vec<usize>) -> Vec<usize> {
-    let max_adapter = adapters.iter().max().unwrap();
-    let device = max_adapter + 3 as usize;
-    let with_socket_and_device = once(&0).chain(adapters.into_iter().chain(once(&device)));
-    with_socket_and_device
-        .sorted()
-        .tuple_windows()
-        .map(|(&a, &b)| b - a)
-        .collect()
-}
+mod arrangements;
+mod chains;
+mod gaps;
 
-fn possible_combinations_count(gaps: &Vec<usize>) -> usize {
-    match gaps.as_slice() {
-        [1, 1] => 2,
-        [1, 1, 1] => 4,
-        [1, 1, 1, 1] => 7,
-        _ => 1,
+fn part_1(adapters: &[usize]) -> Result<usize, Vec<Violation>> {
+    let violations = violations(adapters, 3);
+    if !violations.is_empty() {
+        return Err(violations);
     }
+    let histogram = histogram(adapters, 3);
+    let count = |gap| histogram.get(&gap).copied().unwrap_or_default();
+    Ok(count(1) * count(3))
 }
 
-fn part_1(adapters: &Vec<usize>) -> usize {
-    let gaps = get_gaps(adapters);
-    let gap_one_count = gaps.iter().copied().filter(|&d| d == 1).count();
-    let gap_three_count = gaps.iter().copied().filter(|&d| d == 3).count();
-    gap_one_count * gap_three_count
-}
-
-fn part_2(adapters: &Vec<usize>) -> usize {
-    let gaps = get_gaps(adapters);
-    gaps.iter()
-        .group_by(|&v| v)
-        .into_iter()
-        .map(|(_, gaps)| possible_combinations_count(&gaps.into_iter().copied().collect()))
-        .product()
+fn part_2(adapters: &[usize]) -> BigUint {
+    arrangements(adapters, 3)
 }
 
 fn main() {
@@ -43,6 +28,60 @@ fn main() {
         .lines()
         .map(|adap| adap.parse::<usize>().unwrap())
         .collect();
-    println!("Part 1: {}", part_1(&adapters));
-    println!("Part 2: {}", part_2(&adapters));
+    let max_step = env::args().nth(2).map_or(3, |arg| {
+        arg.parse()
+            .ok()
+            .filter(|&max_step| max_step > 0)
+            .expect("max step must be a positive number")
+    });
+    match env::args().nth(1).as_deref() {
+        Some("arrangements") => println!("{}", arrangements(&adapters, max_step)),
+        Some("chains") => {
+            let extremes = [
+                ("shortest", shortest_chain(&adapters, max_step)),
+                ("longest", longest_chain(&adapters, max_step)),
+                (
+                    "random",
+                    random_chain(&adapters, max_step, &mut rand::thread_rng()),
+                ),
+            ];
+            for (name, chain) in extremes.iter() {
+                match chain {
+                    Some(chain) => println!("{} ({}): {:?}", name, chain.len(), chain),
+                    None => println!("{}: no chain reaches the device", name),
+                }
+            }
+            // chains [max step] [count] lists the first chains in order too
+            let count = env::args()
+                .nth(3)
+                .map_or(0, |arg| arg.parse().expect("chain count"));
+            for chain in chains(&adapters, max_step).take(count) {
+                println!("{:?}", chain);
+            }
+        }
+        Some("gaps") => {
+            for (gap, count) in histogram(&adapters, max_step) {
+                println!("{}: {}", gap, count);
+            }
+            for violation in violations(&adapters, max_step) {
+                println!("violation: {}", violation);
+            }
+            let missing = missing_adapters(&adapters, max_step);
+            if !missing.is_empty() {
+                println!("add adapters rated {:?}", missing);
+            }
+        }
+        _ => {
+            match part_1(&adapters) {
+                Ok(answer) => println!("Part 1: {}", answer),
+                Err(violations) => {
+                    println!("Part 1: can't use every adapter");
+                    for violation in violations {
+                        println!("  {}", violation);
+                    }
+                }
+            }
+            println!("Part 2: {}", part_2(&adapters));
+        }
+    }
 }
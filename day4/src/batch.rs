@@ -0,0 +1,79 @@
+use std::{collections::HashMap, fmt};
+
+pub type Record<'a> = HashMap<&'a str, Vec<&'a str>>;
+
+#[derive(Debug, PartialEq)]
+pub enum BatchErrorKind {
+    MissingSeparator(String),
+    EmptyKey(String),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BatchError {
+    pub record: usize,
+    pub line: usize,
+    pub kind: BatchErrorKind,
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "record {}, line {}: ", self.record, self.line)?;
+        match &self.kind {
+            BatchErrorKind::MissingSeparator(field) => write!(f, "field without ':' ({})", field),
+            BatchErrorKind::EmptyKey(field) => write!(f, "field with empty key ({})", field),
+        }
+    }
+}
+
+fn field(input: &str) -> Result<(&str, &str), BatchErrorKind> {
+    match input.find(':') {
+        None => Err(BatchErrorKind::MissingSeparator(input.into())),
+        Some(0) => Err(BatchErrorKind::EmptyKey(input.into())),
+        Some(idx) => Ok((&input[..idx], &input[idx + 1..])),
+    }
+}
+
+// records are separated by any number of blank (or whitespace-only) lines,
+// `lines` already takes care of stripping `\r` from CRLF endings
+pub fn parse_batch(input: &str) -> Result<Vec<Record<'_>>, BatchError> {
+    let mut records = vec![];
+    let mut current = Record::new();
+    for (line_idx, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                records.push(current);
+                current = Record::new();
+            }
+            continue;
+        }
+        for raw_field in line.split_whitespace() {
+            let (key, value) = field(raw_field).map_err(|kind| BatchError {
+                record: records.len() + 1,
+                line: line_idx + 1,
+                kind,
+            })?;
+            current.entry(key).or_default().push(value);
+        }
+    }
+    if !current.is_empty() {
+        records.push(current);
+    }
+    Ok(records)
+}
+
+#[test]
+fn parse_batch_test() {
+    let records = parse_batch("a:1 b:2\r\nc:3\r\n\r\n  \n\nd:http://x:8080\n\n\n").unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0]["c"], vec!["3"]);
+    assert_eq!(records[1]["d"], vec!["http://x:8080"]);
+
+    assert_eq!(
+        parse_batch("a:1\n\nb:2\nbroken").unwrap_err(),
+        BatchError {
+            record: 2,
+            line: 4,
+            kind: BatchErrorKind::MissingSeparator("broken".into()),
+        }
+    );
+}
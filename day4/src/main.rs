@@ -2,7 +2,11 @@
 extern crate lazy_static;
 use itertools::Itertools;
 use regex::Regex;
-use std::{collections::HashMap, fs::read_to_string};
+use std::fs::read_to_string;
+
+use batch::{parse_batch, Record};
+
+mod batch;
 
 lazy_static! {
     static ref HEIGHT_REGEX: Regex = Regex::new(r"^(\d+)(cm|in)$").unwrap();
@@ -11,8 +15,8 @@ lazy_static! {
         Regex::new(r"^((amb)|(blu)|(brn)|(gry)|(grn)|(hzl)|(oth))$").unwrap();
 }
 
-fn passport_valid(entry: &HashMap<&str, Vec<&str>>) -> bool {
-    let required_keys = vec!["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"];
+fn passport_valid(entry: &Record) -> bool {
+    let required_keys = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"];
     required_keys.iter().all(|&key| entry.contains_key(key))
 }
 
@@ -34,8 +38,8 @@ fn passport_field_valid(key: &str, value: &str) -> bool {
             .collect_tuple::<(_, _, _)>()?;
         let height = (height as &str).parse::<usize>().ok()?;
         match unit {
-            "cm" => Some(height >= 150 && height <= 193),
-            "in" => Some(height >= 59 && height <= 76),
+            "cm" => Some((150..=193).contains(&height)),
+            "in" => Some((59..=76).contains(&height)),
             _ => None,
         }
     };
@@ -52,22 +56,22 @@ fn passport_field_valid(key: &str, value: &str) -> bool {
     }
 }
 
-fn passport_valid_2(entry: &HashMap<&str, Vec<&str>>) -> bool {
-    let required_keys = vec!["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"];
+fn passport_valid_2(entry: &Record) -> bool {
+    let required_keys = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"];
     required_keys.iter().all(|&key| {
-        let value = *entry.get(key).and_then(|vals| vals.get(0)).unwrap_or(&"");
+        let value = *entry.get(key).and_then(|vals| vals.first()).unwrap_or(&"");
         entry.contains_key(key) && passport_field_valid(key, value)
     })
 }
 
-fn part_1(entries: &Vec<HashMap<&str, Vec<&str>>>) -> usize {
+fn part_1(entries: &[Record]) -> usize {
     entries
         .iter()
         .filter(|&entry| passport_valid(entry))
         .count()
 }
 
-fn part_2(entries: &Vec<HashMap<&str, Vec<&str>>>) -> usize {
+fn part_2(entries: &[Record]) -> usize {
     entries
         .iter()
         .filter(|&entry| passport_valid_2(entry))
@@ -76,15 +80,7 @@ fn part_2(entries: &Vec<HashMap<&str, Vec<&str>>>) -> usize {
 
 fn main() {
     let input = read_to_string("./src/input").unwrap();
-    let entries = input
-        .split("\n\n")
-        .map(|entry| {
-            entry
-                .split_whitespace()
-                .map(|field| field.split(':').collect_tuple::<(_, _)>().unwrap())
-                .into_group_map()
-        })
-        .collect();
+    let entries = parse_batch(&input).unwrap_or_else(|err| panic!("{}", err));
     println!("Part 1: {}", part_1(&entries));
     println!("Part 2: {}", part_2(&entries));
 }
@@ -0,0 +1,206 @@
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub enum CodecError {
+    InvalidLength { expected: usize, found: usize },
+    UnknownChar { position: usize, found: char },
+    SeatOutOfRange(usize),
+    UnsupportedGeometry(usize),
+    // both halves use the same char, so passes can't be decoded
+    AmbiguousAlphabet(char),
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodecError::InvalidLength { expected, found } => {
+                write!(f, "expected {} characters, found {}", expected, found)
+            }
+            CodecError::UnknownChar { position, found } => {
+                write!(f, "unknown char {} at position {}", found, position)
+            }
+            CodecError::SeatOutOfRange(seat_id) => write!(f, "seat id {} out of range", seat_id),
+            CodecError::UnsupportedGeometry(bits) => {
+                write!(f, "{} bit boarding passes are not supported", bits)
+            }
+            CodecError::AmbiguousAlphabet(c) => {
+                write!(f, "{} can't stand for both halves", c)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Geometry {
+    row_bits: u32,
+    column_bits: u32,
+    // (lower half, upper half)
+    row_alphabet: (char, char),
+    column_alphabet: (char, char),
+}
+
+impl Default for Geometry {
+    fn default() -> Self {
+        Geometry::new(7, 3, ('F', 'B'), ('L', 'R')).expect("default geometry is valid")
+    }
+}
+
+// reference implementation of range halving, bit decoding is checked against it
+#[cfg(test)]
+fn split_range(range: (u32, u32)) -> ((u32, u32), (u32, u32)) {
+    let midpoint = (range.1 - range.0) / 2 + range.0;
+    ((range.0, midpoint), (midpoint + 1, range.1))
+}
+
+impl Geometry {
+    // seat ids are decoded into a u32, so passes can have at most 32 chars
+    pub fn new(
+        row_bits: u32,
+        column_bits: u32,
+        row_alphabet: (char, char),
+        column_alphabet: (char, char),
+    ) -> Result<Self, CodecError> {
+        let bits = row_bits + column_bits;
+        if bits > 32 {
+            return Err(CodecError::UnsupportedGeometry(bits as usize));
+        }
+        for &(lower, upper) in [row_alphabet, column_alphabet].iter() {
+            if lower == upper {
+                return Err(CodecError::AmbiguousAlphabet(lower));
+            }
+        }
+        Ok(Geometry {
+            row_bits,
+            column_bits,
+            row_alphabet,
+            column_alphabet,
+        })
+    }
+
+    pub fn rows(&self) -> usize {
+        1 << self.row_bits
+    }
+
+    pub fn columns(&self) -> usize {
+        1 << self.column_bits
+    }
+
+    pub fn seat_count(&self) -> usize {
+        self.rows() * self.columns()
+    }
+
+    pub fn pass_len(&self) -> usize {
+        (self.row_bits + self.column_bits) as usize
+    }
+
+    pub fn seat_position(&self, seat_id: usize) -> (usize, usize) {
+        (seat_id / self.columns(), seat_id % self.columns())
+    }
+
+    fn bit(&self, position: usize, c: char) -> Result<u32, CodecError> {
+        let (lower, upper) = if position < self.row_bits as usize {
+            self.row_alphabet
+        } else {
+            self.column_alphabet
+        };
+        match c {
+            c if c == lower => Ok(0),
+            c if c == upper => Ok(1),
+            _ => Err(CodecError::UnknownChar { position, found: c }),
+        }
+    }
+
+    // row bits followed by column bits are exactly the binary seat id
+    pub fn decode(&self, pass: &str) -> Result<usize, CodecError> {
+        let found = pass.chars().count();
+        if found != self.pass_len() {
+            return Err(CodecError::InvalidLength {
+                expected: self.pass_len(),
+                found,
+            });
+        }
+        let seat_id = pass
+            .chars()
+            .enumerate()
+            .try_fold(0u32, |acc, (idx, c)| Ok(acc << 1 | self.bit(idx, c)?))?;
+        Ok(seat_id as usize)
+    }
+
+    pub fn encode(&self, seat_id: usize) -> Result<String, CodecError> {
+        if seat_id >= self.seat_count() {
+            return Err(CodecError::SeatOutOfRange(seat_id));
+        }
+        let (row, column) = self.seat_position(seat_id);
+        let bits_to_chars = |value: usize, bits: u32, (lower, upper): (char, char)| {
+            (0..bits)
+                .rev()
+                .map(move |bit| if value >> bit & 1 == 0 { lower } else { upper })
+        };
+        Ok(bits_to_chars(row, self.row_bits, self.row_alphabet)
+            .chain(bits_to_chars(
+                column,
+                self.column_bits,
+                self.column_alphabet,
+            ))
+            .collect())
+    }
+}
+
+#[test]
+fn split_range_test() {
+    assert_eq!(split_range((0, 127)), ((0, 63), (64, 127)));
+    assert_eq!(split_range((0, 63)), ((0, 31), (32, 63)));
+    assert_eq!(split_range((32, 63)), ((32, 47), (48, 63)));
+    assert_eq!(split_range((32, 47)), ((32, 39), (40, 47)));
+    assert_eq!(split_range((40, 47)), ((40, 43), (44, 47)));
+    assert_eq!(split_range((44, 47)), ((44, 45), (46, 47)));
+    assert_eq!(split_range((44, 45)), ((44, 44), (45, 45)));
+}
+
+#[test]
+fn bit_decoding_matches_split_range_test() {
+    let geometry = Geometry::default();
+    for seat_id in 0..geometry.seat_count() {
+        let pass = geometry.encode(seat_id).unwrap();
+        let (row_path, column_path) = pass.split_at(7);
+        let halve = |range, c| {
+            let (lower, upper) = split_range(range);
+            if c == 'F' || c == 'L' {
+                lower
+            } else {
+                upper
+            }
+        };
+        let (row, _) = row_path.chars().fold((0, 127), halve);
+        let (column, _) = column_path.chars().fold((0, 7), halve);
+        assert_eq!(geometry.decode(&pass), Ok((row * 8 + column) as usize));
+    }
+}
+
+#[test]
+fn codec_test() {
+    let geometry = Geometry::default();
+    assert_eq!(geometry.encode(567).unwrap(), "BFFFBBFRRR");
+    assert_eq!(geometry.decode("BFFFBBFRLL"), Ok(564));
+    assert_eq!(
+        geometry.decode("BFFFBXFRRR"),
+        Err(CodecError::UnknownChar {
+            position: 5,
+            found: 'X'
+        })
+    );
+    assert_eq!(geometry.encode(1024), Err(CodecError::SeatOutOfRange(1024)));
+
+    let wide = Geometry::new(9, 2, ('0', '1'), ('a', 'b')).unwrap();
+    for seat_id in 0..wide.seat_count() {
+        assert_eq!(wide.decode(&wide.encode(seat_id).unwrap()), Ok(seat_id));
+    }
+    assert_eq!(
+        Geometry::new(30, 3, ('F', 'B'), ('L', 'R')).unwrap_err(),
+        CodecError::UnsupportedGeometry(33)
+    );
+    assert_eq!(
+        Geometry::new(7, 3, ('F', 'B'), ('L', 'L')).unwrap_err(),
+        CodecError::AmbiguousAlphabet('L')
+    );
+}
@@ -1,66 +1,75 @@
 use itertools::Itertools;
-use std::fs::read_to_string;
+use std::{env, fs::read_to_string};
 
-fn split_range(range: (u8, u8)) -> ((u8, u8), (u8, u8)) {
-    let midpoint = (range.1 - range.0) / 2 + range.0;
-    ((range.0, midpoint), (midpoint + 1, range.1))
-}
+use codec::Geometry;
+use seat_map::SeatMap;
+use seat_set::SeatSet;
 
-fn take_range(range: (u8, u8), half_char: char) -> (u8, u8) {
-    let (lower, upper) = split_range(range);
-    match half_char {
-        'F' | 'L' => lower,
-        'B' | 'R' => upper,
-        _ => panic!("unknown char {}", half_char),
-    }
-}
+mod codec;
+mod seat_map;
+mod seat_set;
 
+#[cfg(test)]
 fn path_to_seat_id(path: &str) -> usize {
-    let (row_path, column_path) = path.split_at(path.len() - 3);
-    let (row, _) = row_path
-        .chars()
-        .fold((0, 127), |range, c| take_range(range, c));
-    let (column, _) = column_path
-        .chars()
-        .fold((0, 7), |range, c| take_range(range, c));
-    row as usize * 8 + column as usize
+    Geometry::default()
+        .decode(path)
+        .unwrap_or_else(|err| panic!("invalid boarding pass {}: {}", path, err))
 }
 
-fn part_1(paths: &Vec<&str>) -> usize {
-    paths
-        .iter()
-        .map(|&path| path_to_seat_id(path))
-        .max()
-        .expect("there is max seat_id")
+fn part_1(seats: &SeatSet) -> usize {
+    seats.max().expect("there is max seat_id")
 }
 
-fn part_2(paths: &Vec<&str>) -> usize {
-    let mut seat_ids: Vec<usize> = paths.iter().map(|&path| path_to_seat_id(path)).collect();
-    seat_ids.sort();
-    let (left_seat, _) = seat_ids
-        .iter()
-        .tuple_windows()
-        .find(|(&a, &b)| a + 2 == b)
-        .expect("two seats with one space between them");
-    left_seat + 1
+fn part_2(seats: &SeatSet) -> usize {
+    seats.missing().exactly_one().unwrap_or_else(|_| {
+        panic!(
+            "expected exactly one empty seat between {} occupied ones",
+            seats.len()
+        )
+    })
 }
 
-fn main() {
-    let input = read_to_string("./src/input").unwrap();
-    let paths = input.lines().collect();
-    println!("Part 1: {}", part_1(&paths));
-    println!("Part 2: {}", part_2(&paths));
+// prints the whole manifest instead of stopping at the first bad pass
+fn audit(paths: &[&str]) {
+    let geometry = Geometry::default();
+    let map = SeatMap::from_passes(geometry, paths);
+    print!("{}", map.render());
+    let analysis = map.analyse();
+    println!("empty seats: {}", analysis.empty_seats.len());
+    println!("front gap: {:?}", analysis.front_gap);
+    println!("back gap: {:?}", analysis.back_gap);
+    for seat_id in analysis.interior_empty {
+        let (row, column) = geometry.seat_position(seat_id);
+        let pass = geometry
+            .encode(seat_id)
+            .expect("seat id comes from the map");
+        println!(
+            "interior empty: {} (row {}, column {}, pass {})",
+            seat_id, row, column, pass
+        );
+    }
+    for (seat_id, count) in analysis.duplicates {
+        println!("seat {} taken by {} passes", seat_id, count);
+    }
+    for invalid in map.invalid_passes() {
+        println!(
+            "invalid pass at line {}: {} ({})",
+            invalid.line, invalid.pass, invalid.error
+        );
+    }
 }
 
-#[test]
-fn split_range_test() {
-    assert_eq!(split_range((0, 127)), ((0, 63), (64, 127)));
-    assert_eq!(split_range((0, 63)), ((0, 31), (32, 63)));
-    assert_eq!(split_range((32, 63)), ((32, 47), (48, 63)));
-    assert_eq!(split_range((32, 47)), ((32, 39), (40, 47)));
-    assert_eq!(split_range((40, 47)), ((40, 43), (44, 47)));
-    assert_eq!(split_range((44, 47)), ((44, 45), (46, 47)));
-    assert_eq!(split_range((44, 45)), ((44, 44), (45, 45)));
+fn main() {
+    let input = read_to_string("./src/input").unwrap();
+    let paths: Vec<&str> = input.lines().collect();
+    if env::args().nth(1).as_deref() == Some("audit") {
+        audit(&paths);
+        return;
+    }
+    let seats = SeatSet::decode_manifest(&Geometry::default(), &paths)
+        .unwrap_or_else(|(line, err)| panic!("invalid boarding pass at line {}: {}", line, err));
+    println!("Part 1: {}", part_1(&seats));
+    println!("Part 2: {}", part_2(&seats));
 }
 
 #[test]
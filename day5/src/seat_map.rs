@@ -0,0 +1,124 @@
+use std::ops::Range;
+
+use crate::codec::{CodecError, Geometry};
+
+#[derive(Debug)]
+pub struct InvalidPass {
+    pub line: usize,
+    pub pass: String,
+    pub error: CodecError,
+}
+
+#[derive(Debug)]
+pub struct SeatMap {
+    geometry: Geometry,
+    // how many boarding passes point at each seat id
+    occupancy: Vec<usize>,
+    invalid: Vec<InvalidPass>,
+}
+
+#[derive(Debug)]
+pub struct Analysis {
+    pub empty_seats: Vec<usize>,
+    pub front_gap: Range<usize>,
+    pub back_gap: Range<usize>,
+    // empty seats between the first and the last occupied one
+    pub interior_empty: Vec<usize>,
+    pub duplicates: Vec<(usize, usize)>,
+}
+
+impl SeatMap {
+    pub fn from_passes(geometry: Geometry, passes: &[&str]) -> Self {
+        let mut occupancy = vec![0; geometry.seat_count()];
+        let mut invalid = vec![];
+        for (idx, &pass) in passes.iter().enumerate() {
+            match geometry.decode(pass) {
+                Ok(seat_id) => occupancy[seat_id] += 1,
+                Err(error) => invalid.push(InvalidPass {
+                    line: idx + 1,
+                    pass: pass.into(),
+                    error,
+                }),
+            }
+        }
+        SeatMap {
+            geometry,
+            occupancy,
+            invalid,
+        }
+    }
+
+    pub fn is_occupied(&self, seat_id: usize) -> bool {
+        self.occupancy.get(seat_id).is_some_and(|&c| c > 0)
+    }
+
+    pub fn occupied_seats(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.occupancy.len()).filter(move |&id| self.is_occupied(id))
+    }
+
+    pub fn invalid_passes(&self) -> &[InvalidPass] {
+        &self.invalid
+    }
+
+    pub fn render(&self) -> String {
+        let columns = self.geometry.columns();
+        self.occupancy
+            .chunks(columns)
+            .enumerate()
+            .map(|(row, seats)| {
+                let seats: String = seats
+                    .iter()
+                    .map(|&c| match c {
+                        0 => '.',
+                        1 => '#',
+                        _ => '!',
+                    })
+                    .collect();
+                format!("{:>4} {}\n", row, seats)
+            })
+            .collect()
+    }
+
+    pub fn analyse(&self) -> Analysis {
+        let seat_count = self.occupancy.len();
+        let first_occupied = self.occupied_seats().next().unwrap_or(seat_count);
+        let last_occupied = self.occupied_seats().last().map_or(seat_count, |id| id + 1);
+        let empty_seats: Vec<usize> = (0..seat_count)
+            .filter(|&id| !self.is_occupied(id))
+            .collect();
+        let interior_empty = empty_seats
+            .iter()
+            .copied()
+            .filter(|&id| id > first_occupied && id < last_occupied)
+            .collect();
+        let duplicates = self
+            .occupancy
+            .iter()
+            .enumerate()
+            .filter(|(_, &c)| c > 1)
+            .map(|(id, &c)| (id, c))
+            .collect();
+        Analysis {
+            empty_seats,
+            front_gap: 0..first_occupied,
+            back_gap: last_occupied.max(first_occupied)..seat_count,
+            interior_empty,
+            duplicates,
+        }
+    }
+}
+
+#[test]
+fn seat_map_test() {
+    let geometry = Geometry::new(2, 1, ('F', 'B'), ('L', 'R')).unwrap();
+    let map = SeatMap::from_passes(geometry, &["FBL", "FBL", "BFL", "BFR", "FXL"]);
+    assert_eq!(map.render(), "   0 ..\n   1 !.\n   2 ##\n   3 ..\n");
+
+    let analysis = map.analyse();
+    assert_eq!(analysis.empty_seats, vec![0, 1, 3, 6, 7]);
+    assert_eq!(analysis.front_gap, 0..2);
+    assert_eq!(analysis.back_gap, 6..8);
+    assert_eq!(analysis.interior_empty, vec![3]);
+    assert_eq!(analysis.duplicates, vec![(2, 2)]);
+    assert_eq!(map.invalid_passes()[0].line, 5);
+}
@@ -0,0 +1,100 @@
+use crate::codec::{CodecError, Geometry};
+
+const WORD_BITS: usize = 64;
+
+// bitset of seat ids, iteration yields ids in ascending order
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeatSet {
+    words: Vec<u64>,
+}
+
+impl SeatSet {
+    pub fn with_capacity(seat_count: usize) -> Self {
+        SeatSet {
+            words: vec![0; seat_count.div_ceil(WORD_BITS)],
+        }
+    }
+
+    pub fn decode_manifest(
+        geometry: &Geometry,
+        passes: &[&str],
+    ) -> Result<Self, (usize, CodecError)> {
+        let mut set = SeatSet::with_capacity(geometry.seat_count());
+        for (idx, pass) in passes.iter().enumerate() {
+            let seat_id = geometry.decode(pass).map_err(|err| (idx + 1, err))?;
+            set.insert(seat_id);
+        }
+        Ok(set)
+    }
+
+    pub fn insert(&mut self, seat_id: usize) {
+        let word = seat_id / WORD_BITS;
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        self.words[word] |= 1 << (seat_id % WORD_BITS);
+    }
+
+    pub fn contains(&self, seat_id: usize) -> bool {
+        self.words
+            .get(seat_id / WORD_BITS)
+            .is_some_and(|word| word >> (seat_id % WORD_BITS) & 1 == 1)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(word_idx, &word)| {
+            let mut rest = word;
+            std::iter::from_fn(move || {
+                if rest == 0 {
+                    return None;
+                }
+                let bit = rest.trailing_zeros() as usize;
+                rest &= rest - 1;
+                Some(word_idx * WORD_BITS + bit)
+            })
+        })
+    }
+
+    pub fn min(&self) -> Option<usize> {
+        self.iter().next()
+    }
+
+    pub fn max(&self) -> Option<usize> {
+        self.words
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, &word)| word != 0)
+            .map(|(word_idx, &word)| word_idx * WORD_BITS + 63 - word.leading_zeros() as usize)
+    }
+
+    pub fn len(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    // ids missing between the lowest and the highest seat in the set
+    pub fn missing(&self) -> impl Iterator<Item = usize> + '_ {
+        let range = match (self.min(), self.max()) {
+            (Some(min), Some(max)) => min..max,
+            _ => 0..0,
+        };
+        range.filter(move |&seat_id| !self.contains(seat_id))
+    }
+}
+
+#[test]
+fn seat_set_test() {
+    let mut set = SeatSet::with_capacity(8);
+    for seat_id in [3, 130, 64, 5, 4, 7] {
+        set.insert(seat_id);
+    }
+    assert_eq!(set.iter().collect::<Vec<_>>(), vec![3, 4, 5, 7, 64, 130]);
+    assert_eq!((set.min(), set.max(), set.len()), (Some(3), Some(130), 6));
+    assert_eq!(set.missing().take(3).collect::<Vec<_>>(), vec![6, 8, 9]);
+
+    let manifest = SeatSet::decode_manifest(&Geometry::default(), &["BFFFBBFRRR", "BFFFBBFRLR"]);
+    assert_eq!(manifest.unwrap().missing().collect::<Vec<_>>(), vec![566]);
+}
@@ -0,0 +1,96 @@
+use std::iter::FromIterator;
+
+const WORD_BITS: usize = 64;
+
+// set of question indices answered "yes", the last word is never zero so the
+// derived equality compares sets
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Answers {
+    words: Vec<u64>,
+}
+
+impl Answers {
+    pub fn insert(&mut self, question: usize) {
+        let word = question / WORD_BITS;
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        self.words[word] |= 1 << (question % WORD_BITS);
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn union(&self, other: &Answers) -> Answers {
+        let len = self.words.len().max(other.words.len());
+        let words = (0..len)
+            .map(|idx| self.word(idx) | other.word(idx))
+            .collect();
+        Answers::trimmed(words)
+    }
+
+    pub fn intersection(&self, other: &Answers) -> Answers {
+        let len = self.words.len().min(other.words.len());
+        let words = (0..len)
+            .map(|idx| self.word(idx) & other.word(idx))
+            .collect();
+        Answers::trimmed(words)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(word_idx, &word)| {
+            let mut rest = word;
+            std::iter::from_fn(move || {
+                if rest == 0 {
+                    return None;
+                }
+                let bit = rest.trailing_zeros() as usize;
+                rest &= rest - 1;
+                Some(word_idx * WORD_BITS + bit)
+            })
+        })
+    }
+
+    fn trimmed(mut words: Vec<u64>) -> Answers {
+        while words.last() == Some(&0) {
+            words.pop();
+        }
+        Answers { words }
+    }
+
+    fn word(&self, idx: usize) -> u64 {
+        self.words.get(idx).copied().unwrap_or(0)
+    }
+}
+
+impl FromIterator<usize> for Answers {
+    fn from_iter<T: IntoIterator<Item = usize>>(iter: T) -> Self {
+        let mut answers = Answers::default();
+        for question in iter {
+            answers.insert(question);
+        }
+        answers
+    }
+}
+
+#[test]
+fn answers_test() {
+    let a: Answers = vec![0, 2, 70].into_iter().collect();
+    let b: Answers = vec![2, 3].into_iter().collect();
+    assert_eq!(a.union(&b).iter().collect::<Vec<_>>(), vec![0, 2, 3, 70]);
+    assert_eq!(a.intersection(&b).iter().collect::<Vec<_>>(), vec![2]);
+    let contains = |answers: &Answers, question| answers.iter().any(|q| q == question);
+    assert_eq!(
+        (a.len(), contains(&a, 70), contains(&a, 1)),
+        (3, true, false)
+    );
+    assert_eq!(a.intersection(&b), vec![2].into_iter().collect());
+    let (one, two): (Answers, Answers) =
+        (vec![1].into_iter().collect(), vec![2].into_iter().collect());
+    assert_eq!(one.intersection(&two), Answers::default());
+    assert_eq!(
+        a.intersection(&vec![70].into_iter().collect()),
+        vec![70].into_iter().collect()
+    );
+}
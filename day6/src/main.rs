@@ -1,51 +1,197 @@
-use std::fs::read_to_string;
+use std::{convert::TryFrom, env, fs::read_to_string};
+
+use answers::Answers;
+use questionnaire::{Questionnaire, Tokenizer, UnknownQuestion};
+use regex::Regex;
+use stats::SurveyStats;
+
+mod answers;
+mod questionnaire;
+mod stats;
 
 #[derive(Debug)]
 struct Group {
-    people: Vec<Vec<char>>,
+    people: Vec<Answers>,
 }
 
-impl From<&str> for Group {
-    fn from(input: &str) -> Self {
-        let people = input.lines().map(|line| line.chars().collect()).collect();
-        Group { people }
+impl TryFrom<&str> for Group {
+    type Error = UnknownQuestion;
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        Questionnaire::letters().group(input)
     }
 }
 
 impl Group {
-    fn uniq_questions(&self) -> impl Iterator<Item = &char> {
-        let mut all_questions: Vec<&char> = self.people.iter().flatten().collect();
-        all_questions.sort();
-        all_questions.dedup();
-        all_questions.into_iter()
+    fn union(&self) -> Answers {
+        self.people
+            .iter()
+            .fold(Answers::default(), |acc, person| acc.union(person))
+    }
+
+    fn intersection(&self) -> Answers {
+        let mut people = self.people.iter();
+        let first = people.next().cloned().unwrap_or_default();
+        people.fold(first, |acc, person| acc.intersection(person))
+    }
+
+    // how many people in the group answered each question, indexed by question
+    fn frequencies(&self) -> Vec<usize> {
+        let mut frequencies = vec![];
+        for question in self.people.iter().flat_map(Answers::iter) {
+            if question >= frequencies.len() {
+                frequencies.resize(question + 1, 0);
+            }
+            frequencies[question] += 1;
+        }
+        frequencies
+    }
+
+    fn answered_by_at_least(&self, k: usize) -> Answers {
+        self.frequencies()
+            .into_iter()
+            .enumerate()
+            .filter(|&(_, count)| count > 0 && count >= k)
+            .map(|(question, _)| question)
+            .collect()
+    }
+
+    fn answered_by_exactly_one(&self) -> Answers {
+        self.frequencies()
+            .into_iter()
+            .enumerate()
+            .filter(|&(_, count)| count == 1)
+            .map(|(question, _)| question)
+            .collect()
+    }
+
+    fn uniq_questions<'a>(
+        &self,
+        questionnaire: &'a Questionnaire,
+    ) -> impl Iterator<Item = &'a str> + 'a {
+        questionnaire.ids(&self.union()).into_iter()
     }
 
-    fn every_questions(&self) -> impl Iterator<Item = &char> + '_ {
-        self.uniq_questions()
-            .filter(move |q| self.people.iter().all(|person| person.contains(q)))
+    fn every_questions<'a>(
+        &self,
+        questionnaire: &'a Questionnaire,
+    ) -> impl Iterator<Item = &'a str> + 'a {
+        questionnaire.ids(&self.intersection()).into_iter()
     }
 }
 
-fn part_1(groups: &Vec<Group>) -> usize {
-    groups
-        .iter()
-        .map(|group| group.uniq_questions().count())
-        .sum()
+// how many people answered each question across all groups
+fn histogram(groups: &[Group]) -> Vec<usize> {
+    groups.iter().fold(vec![], |mut acc, group| {
+        let frequencies = group.frequencies();
+        if frequencies.len() > acc.len() {
+            acc.resize(frequencies.len(), 0);
+        }
+        for (question, count) in frequencies.into_iter().enumerate() {
+            acc[question] += count;
+        }
+        acc
+    })
 }
 
-fn part_2(groups: &Vec<Group>) -> usize {
-    groups
-        .iter()
-        .map(|group| group.every_questions().count())
-        .sum()
+fn part_1(groups: &[Group]) -> usize {
+    groups.iter().map(|group| group.union().len()).sum()
+}
+
+fn part_2(groups: &[Group]) -> usize {
+    groups.iter().map(|group| group.intersection().len()).sum()
+}
+
+// per group answers to the set queries, `k` is the threshold for "at least"
+fn queries(groups: &[Group], questionnaire: &Questionnaire, k: usize) {
+    for (idx, group) in groups.iter().enumerate() {
+        let uniq: Vec<&str> = group.uniq_questions(questionnaire).collect();
+        let every: Vec<&str> = group.every_questions(questionnaire).collect();
+        println!(
+            "group {}: anyone {:?}, everyone {:?}, exactly one {:?}, at least {} {:?}",
+            idx + 1,
+            uniq,
+            every,
+            questionnaire.ids(&group.answered_by_exactly_one()),
+            k,
+            questionnaire.ids(&group.answered_by_at_least(k)),
+        );
+    }
+}
+
+// letters by default. leading `--separator <char>` or `--pattern <regex>` pick
+// another tokenizer and `--questions <id,id,..>` rejects any other id, without
+// it a custom tokenizer accepts every id it finds
+fn questionnaire(args: &mut Vec<String>) -> Questionnaire {
+    let mut tokenizer = None;
+    let mut questions: Option<Vec<String>> = None;
+    while args.len() > 1 {
+        match args[0].as_str() {
+            "--separator" => {
+                let separator = args[1].chars().next().expect("separator char");
+                tokenizer = Some(Tokenizer::Separated(separator));
+            }
+            "--pattern" => {
+                let pattern = Regex::new(&args[1]).unwrap_or_else(|err| panic!("{}", err));
+                tokenizer = Some(Tokenizer::Pattern(pattern));
+            }
+            "--questions" => {
+                questions = Some(args[1].split(',').map(|id| id.trim().to_string()).collect())
+            }
+            _ => break,
+        }
+        args.drain(..2);
+    }
+    match (tokenizer, questions) {
+        (None, None) => Questionnaire::letters(),
+        (tokenizer, Some(questions)) => Questionnaire::closed(
+            tokenizer.unwrap_or(Tokenizer::Letters),
+            questions.iter().map(String::as_str),
+        ),
+        (Some(tokenizer), None) => Questionnaire::open(tokenizer),
+    }
 }
 
 fn main() {
     let input = read_to_string("./src/input").unwrap();
-    let groups = input
-        .split("\n\n")
-        .map(|group_str| group_str.into())
-        .collect();
-    println!("Part 1: {}", part_1(&groups));
-    println!("Part 2: {}", part_2(&groups));
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let mut questionnaire = questionnaire(&mut args);
+    let groups = questionnaire
+        .groups(&input)
+        .unwrap_or_else(|err| panic!("{}", err));
+    let stats = || SurveyStats::new(&groups, &questionnaire);
+    match args.first().map(String::as_str) {
+        Some("csv") => print!("{}", stats().groups_csv()),
+        Some("questions-csv") => print!("{}", stats().questions_csv()),
+        Some("json") => println!("{}", stats().to_json()),
+        Some("queries") => {
+            let k = args.get(1).map_or(2, |arg| arg.parse().expect("threshold"));
+            queries(&groups, &questionnaire, k);
+        }
+        _ => {
+            println!("Part 1: {}", part_1(&groups));
+            println!("Part 2: {}", part_2(&groups));
+        }
+    }
+}
+
+#[test]
+fn group_queries_test() {
+    let letters = Questionnaire::letters();
+    let group = Group::try_from("abc\nab\nad").unwrap();
+    assert_eq!(group.uniq_questions(&letters).collect::<String>(), "abcd");
+    assert_eq!(group.every_questions(&letters).collect::<String>(), "a");
+    assert_eq!(
+        group.answered_by_at_least(2).iter().collect::<Vec<_>>(),
+        vec![0, 1]
+    );
+    assert_eq!(
+        group.answered_by_exactly_one().iter().collect::<Vec<_>>(),
+        vec![2, 3]
+    );
+    assert!(Group::try_from("aB").is_err());
+    assert_eq!(
+        histogram(&[group, Group::try_from("b").unwrap()]),
+        vec![3, 3, 1, 1]
+    );
 }
@@ -0,0 +1,165 @@
+use std::{collections::HashMap, fmt};
+
+use regex::Regex;
+
+use crate::{answers::Answers, Group};
+
+#[derive(Debug)]
+pub enum Tokenizer {
+    // every non-whitespace char is one answer
+    Letters,
+    // answers are ids separated by the given char
+    Separated(char),
+    // every match of the pattern is one answer
+    Pattern(Regex),
+}
+
+impl Tokenizer {
+    fn tokens<'a>(&self, line: &'a str) -> Vec<&'a str> {
+        match self {
+            Tokenizer::Letters => line
+                .char_indices()
+                .filter(|(_, c)| !c.is_whitespace())
+                .map(|(idx, c)| &line[idx..idx + c.len_utf8()])
+                .collect(),
+            Tokenizer::Separated(separator) => line
+                .split(*separator)
+                .map(str::trim)
+                .filter(|token| !token.is_empty())
+                .collect(),
+            Tokenizer::Pattern(pattern) => pattern.find_iter(line).map(|m| m.as_str()).collect(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct UnknownQuestion {
+    pub line: usize,
+    pub id: String,
+}
+
+impl fmt::Display for UnknownQuestion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown question /{}/ at line {}", self.id, self.line)
+    }
+}
+
+// maps question ids to the indices stored in `Answers`
+#[derive(Debug)]
+pub struct Questionnaire {
+    tokenizer: Tokenizer,
+    ids: Vec<String>,
+    indices: HashMap<String, usize>,
+    // closed questionnaires reject ids they were not created with
+    closed: bool,
+}
+
+impl Questionnaire {
+    pub fn open(tokenizer: Tokenizer) -> Self {
+        Questionnaire {
+            tokenizer,
+            ids: vec![],
+            indices: HashMap::new(),
+            closed: false,
+        }
+    }
+
+    pub fn closed<'a>(tokenizer: Tokenizer, ids: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut questionnaire = Questionnaire::open(tokenizer);
+        for id in ids {
+            questionnaire.index_of(id);
+        }
+        questionnaire.closed = true;
+        questionnaire
+    }
+
+    pub fn letters() -> Self {
+        let letters: Vec<String> = ('a'..='z').map(String::from).collect();
+        Questionnaire::closed(Tokenizer::Letters, letters.iter().map(String::as_str))
+    }
+
+    fn index_of(&mut self, id: &str) -> Option<usize> {
+        if let Some(&idx) = self.indices.get(id) {
+            return Some(idx);
+        }
+        if self.closed {
+            return None;
+        }
+        self.ids.push(id.into());
+        self.indices.insert(id.into(), self.ids.len() - 1);
+        Some(self.ids.len() - 1)
+    }
+
+    pub fn id(&self, idx: usize) -> &str {
+        &self.ids[idx]
+    }
+
+    pub fn ids(&self, answers: &Answers) -> Vec<&str> {
+        answers.iter().map(|idx| self.id(idx)).collect()
+    }
+
+    pub fn group(&mut self, input: &str) -> Result<Group, UnknownQuestion> {
+        self.group_at(input, 1)
+    }
+
+    // lines without any answer, like extra blank lines between groups, are no one
+    fn group_at(&mut self, input: &str, first_line: usize) -> Result<Group, UnknownQuestion> {
+        let mut people = vec![];
+        for (line_idx, line) in input.lines().enumerate() {
+            let tokens = self.tokenizer.tokens(line);
+            if tokens.is_empty() {
+                continue;
+            }
+            let mut person = Answers::default();
+            for token in tokens {
+                let idx = self.index_of(token).ok_or_else(|| UnknownQuestion {
+                    line: first_line + line_idx,
+                    id: token.into(),
+                })?;
+                person.insert(idx);
+            }
+            people.push(person);
+        }
+        Ok(Group { people })
+    }
+
+    // groups are separated by blank lines, errors report lines of the whole input
+    pub fn groups(&mut self, input: &str) -> Result<Vec<Group>, UnknownQuestion> {
+        let mut line = 1;
+        let mut groups = vec![];
+        for group_str in input.split("\n\n") {
+            groups.push(self.group_at(group_str, line)?);
+            line += group_str.lines().count() + 1;
+        }
+        Ok(groups)
+    }
+}
+
+#[test]
+fn questionnaire_test() {
+    let mut letters = Questionnaire::letters();
+    assert_eq!(
+        letters.groups("ab\nc\n\na b\na1").unwrap_err(),
+        UnknownQuestion {
+            line: 5,
+            id: "1".into()
+        }
+    );
+
+    let mut ids = Questionnaire::open(Tokenizer::Separated(','));
+    let group = ids.group("q10, q2\nq2,q3\n").unwrap();
+    assert_eq!(ids.ids(&group.union()), vec!["q10", "q2", "q3"]);
+    assert_eq!(ids.ids(&group.intersection()), vec!["q2"]);
+
+    let mut pattern = Questionnaire::closed(
+        Tokenizer::Pattern(Regex::new(r"[A-Z]\d").unwrap()),
+        vec!["A1", "B2"],
+    );
+    assert_eq!(pattern.group("A1B2 A1").unwrap().union().len(), 2);
+    assert!(pattern.group("C3").is_err());
+
+    let groups = letters.groups("ab\nb\n\n\n  \nab\n").unwrap();
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups[1].people.len(), 1);
+    assert_eq!(letters.ids(&groups[1].intersection()), vec!["a", "b"]);
+}
@@ -0,0 +1,200 @@
+use crate::{histogram, questionnaire::Questionnaire, Group};
+
+#[derive(Debug, PartialEq)]
+pub struct GroupStats {
+    pub size: usize,
+    pub distinct: usize,
+    pub unanimous: usize,
+    pub most_common: Vec<String>,
+    pub least_common: Vec<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SurveyStats {
+    pub groups: Vec<GroupStats>,
+    pub people: usize,
+    // (question, how many people answered it)
+    pub frequencies: Vec<(String, usize)>,
+    pub most_common: Vec<String>,
+    pub least_common: Vec<String>,
+    // (answers given, how many people gave that many answers)
+    pub answers_per_person: Vec<(usize, usize)>,
+}
+
+fn extremes(frequencies: &[(String, usize)]) -> (Vec<String>, Vec<String>) {
+    let max = frequencies.iter().map(|&(_, c)| c).max();
+    let min = frequencies.iter().map(|&(_, c)| c).min();
+    let with_count = |count| {
+        frequencies
+            .iter()
+            .filter(|&&(_, c)| Some(c) == count)
+            .map(|(q, _)| q.clone())
+            .collect()
+    };
+    (with_count(max), with_count(min))
+}
+
+fn answered(frequencies: Vec<usize>, questionnaire: &Questionnaire) -> Vec<(String, usize)> {
+    frequencies
+        .into_iter()
+        .enumerate()
+        .filter(|&(_, count)| count > 0)
+        .map(|(question, count)| (questionnaire.id(question).into(), count))
+        .collect()
+}
+
+impl GroupStats {
+    pub fn new(group: &Group, questionnaire: &Questionnaire) -> Self {
+        let frequencies = answered(group.frequencies(), questionnaire);
+        let (most_common, least_common) = extremes(&frequencies);
+        GroupStats {
+            size: group.people.len(),
+            distinct: frequencies.len(),
+            unanimous: group.intersection().len(),
+            most_common,
+            least_common,
+        }
+    }
+}
+
+impl SurveyStats {
+    pub fn new(groups: &[Group], questionnaire: &Questionnaire) -> Self {
+        let frequencies = answered(histogram(groups), questionnaire);
+        let (most_common, least_common) = extremes(&frequencies);
+        let mut answers_per_person: Vec<usize> = vec![];
+        for person in groups.iter().flat_map(|group| group.people.iter()) {
+            if person.len() >= answers_per_person.len() {
+                answers_per_person.resize(person.len() + 1, 0);
+            }
+            answers_per_person[person.len()] += 1;
+        }
+        SurveyStats {
+            groups: groups
+                .iter()
+                .map(|group| GroupStats::new(group, questionnaire))
+                .collect(),
+            people: groups.iter().map(|group| group.people.len()).sum(),
+            frequencies,
+            most_common,
+            least_common,
+            answers_per_person: answers_per_person
+                .into_iter()
+                .enumerate()
+                .filter(|&(_, people)| people > 0)
+                .collect(),
+        }
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => escaped += "\\\"",
+            '\\' => escaped += "\\\\",
+            c if (c as u32) < 0x20 => escaped += &format!("\\u{:04x}", c as u32),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn json_strings(values: &[String]) -> String {
+    let quoted: Vec<String> = values.iter().map(|v| json_string(v)).collect();
+    format!("[{}]", quoted.join(","))
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.into()
+    }
+}
+
+impl SurveyStats {
+    pub fn groups_csv(&self) -> String {
+        let mut csv = String::from("group,size,distinct,unanimous,most_common,least_common\n");
+        for (idx, group) in self.groups.iter().enumerate() {
+            csv += &format!(
+                "{},{},{},{},{},{}\n",
+                idx + 1,
+                group.size,
+                group.distinct,
+                group.unanimous,
+                csv_field(&group.most_common.join(" ")),
+                csv_field(&group.least_common.join(" ")),
+            );
+        }
+        csv
+    }
+
+    pub fn questions_csv(&self) -> String {
+        let mut csv = String::from("question,people\n");
+        for (question, count) in self.frequencies.iter() {
+            csv += &format!("{},{}\n", csv_field(question), count);
+        }
+        csv
+    }
+
+    pub fn to_json(&self) -> String {
+        let groups: Vec<String> = self
+            .groups
+            .iter()
+            .map(|group| {
+                format!(
+                    "{{\"size\":{},\"distinct\":{},\"unanimous\":{},\"most_common\":{},\"least_common\":{}}}",
+                    group.size,
+                    group.distinct,
+                    group.unanimous,
+                    json_strings(&group.most_common),
+                    json_strings(&group.least_common),
+                )
+            })
+            .collect();
+        let frequencies: Vec<String> = self
+            .frequencies
+            .iter()
+            .map(|(question, count)| format!("{}:{}", json_string(question), count))
+            .collect();
+        let answers_per_person: Vec<String> = self
+            .answers_per_person
+            .iter()
+            .map(|(answers, people)| format!("\"{}\":{}", answers, people))
+            .collect();
+        format!(
+            "{{\"groups\":[{}],\"people\":{},\"frequencies\":{{{}}},\"most_common\":{},\"least_common\":{},\"answers_per_person\":{{{}}}}}",
+            groups.join(","),
+            self.people,
+            frequencies.join(","),
+            json_strings(&self.most_common),
+            json_strings(&self.least_common),
+            answers_per_person.join(","),
+        )
+    }
+}
+
+#[test]
+fn survey_stats_test() {
+    use std::convert::TryFrom;
+
+    let groups = vec![
+        Group::try_from("abc\nab\nad").unwrap(),
+        Group::try_from("b").unwrap(),
+    ];
+    let stats = SurveyStats::new(&groups, &Questionnaire::letters());
+    assert_eq!(
+        stats.groups_csv(),
+        "group,size,distinct,unanimous,most_common,least_common\n1,3,4,1,a,c d\n2,1,1,1,b,b\n"
+    );
+    assert_eq!(
+        stats.to_json(),
+        concat!(
+            r#"{"groups":[{"size":3,"distinct":4,"unanimous":1,"most_common":["a"],"least_common":["c","d"]},"#,
+            r#"{"size":1,"distinct":1,"unanimous":1,"most_common":["b"],"least_common":["b"]}],"#,
+            r#""people":4,"frequencies":{"a":3,"b":3,"c":1,"d":1},"most_common":["a","b"],"#,
+            r#""least_common":["c","d"],"answers_per_person":{"1":1,"2":2,"3":1}}"#,
+        )
+    );
+}
@@ -0,0 +1,255 @@
+use std::{collections::VecDeque, fmt};
+
+use crate::{
+    graph::{BagGraph, NodeIdx},
+    parser::{BagId, BagRule},
+};
+
+#[derive(Debug, Default)]
+pub struct ExportOptions {
+    // bag whose contents and containers get highlighted
+    pub highlight: Option<BagId>,
+    // keep only bags at most this many rules away from the highlighted bag,
+    // or from the outermost bags when nothing is highlighted
+    pub depth: Option<usize>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Role {
+    Focus,
+    Content,
+    Container,
+    Other,
+}
+
+struct Subgraph {
+    graph: BagGraph,
+    roles: Vec<Role>,
+    included: Vec<bool>,
+}
+
+// error for a highlighted bag that no rule mentions
+#[derive(Debug, PartialEq)]
+pub struct UnknownBag(pub BagId);
+
+impl fmt::Display for UnknownBag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no rule mentions {}", self.0)
+    }
+}
+
+// breadth first distances from the roots following `edges`
+fn walk<'a, F>(graph: &'a BagGraph, roots: &[NodeIdx], edges: F) -> Vec<Option<usize>>
+where
+    F: Fn(NodeIdx) -> &'a [(usize, NodeIdx)],
+{
+    let mut distances = vec![None; graph.len()];
+    let mut queue: VecDeque<NodeIdx> = roots.iter().copied().collect();
+    for &root in roots {
+        distances[root] = Some(0);
+    }
+    while let Some(node) = queue.pop_front() {
+        let distance = distances[node].unwrap();
+        for &(_, next) in edges(node) {
+            if distances[next].is_none() {
+                distances[next] = Some(distance + 1);
+                queue.push_back(next);
+            }
+        }
+    }
+    distances
+}
+
+impl Subgraph {
+    fn new(rules: &[BagRule], options: &ExportOptions) -> Result<Self, UnknownBag> {
+        let graph = BagGraph::from(rules);
+        let mut roles = vec![Role::Other; graph.len()];
+        // distance of every bag from the focus or the outermost bags, contents
+        // and containers are walked separately so siblings aren't pulled in
+        let distances: Vec<Option<usize>> = match &options.highlight {
+            Some(id) => {
+                let focus = graph.node(id).ok_or(UnknownBag(id.clone()))?;
+                let contents = walk(&graph, &[focus], |n| graph.contents(n));
+                let containers = walk(&graph, &[focus], |n| graph.containers(n));
+                for node in 0..graph.len() {
+                    if contents[node].is_some() {
+                        roles[node] = Role::Content;
+                    } else if containers[node].is_some() {
+                        roles[node] = Role::Container;
+                    }
+                }
+                roles[focus] = Role::Focus;
+                contents
+                    .into_iter()
+                    .zip(containers)
+                    .map(|(down, up)| down.into_iter().chain(up).min())
+                    .collect()
+            }
+            None => {
+                let roots: Vec<NodeIdx> = (0..graph.len())
+                    .filter(|&node| graph.containers(node).is_empty())
+                    .collect();
+                walk(&graph, &roots, |n| graph.contents(n))
+            }
+        };
+        let included = match options.depth {
+            Some(depth) => distances
+                .into_iter()
+                .map(|distance| distance.is_some_and(|d| d <= depth))
+                .collect(),
+            None => vec![true; graph.len()],
+        };
+        Ok(Subgraph {
+            graph,
+            roles,
+            included,
+        })
+    }
+
+    fn nodes(&self) -> impl Iterator<Item = NodeIdx> + '_ {
+        (0..self.graph.len()).filter(move |&node| self.included[node])
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (NodeIdx, usize, NodeIdx)> + '_ {
+        self.nodes().flat_map(move |node| {
+            self.graph
+                .contents(node)
+                .iter()
+                .filter(move |&&(_, inner)| self.included[inner])
+                .map(move |&(count, inner)| (node, count, inner))
+        })
+    }
+}
+
+fn label(id: &BagId) -> String {
+    id.to_string().replace('"', "\\\"")
+}
+
+pub fn to_dot(rules: &[BagRule], options: &ExportOptions) -> Result<String, UnknownBag> {
+    let subgraph = Subgraph::new(rules, options)?;
+    let mut dot = String::from("digraph bags {\n");
+    for node in subgraph.nodes() {
+        let style = match subgraph.roles[node] {
+            Role::Focus => " [style=filled, fillcolor=gold]",
+            Role::Content => " [style=filled, fillcolor=lightblue]",
+            Role::Container => " [style=filled, fillcolor=lightpink]",
+            Role::Other => "",
+        };
+        dot += &format!("    \"{}\"{};\n", label(subgraph.graph.id(node)), style);
+    }
+    for (from, count, to) in subgraph.edges() {
+        dot += &format!(
+            "    \"{}\" -> \"{}\" [label=\"{}\"];\n",
+            label(subgraph.graph.id(from)),
+            label(subgraph.graph.id(to)),
+            count
+        );
+    }
+    dot += "}\n";
+    Ok(dot)
+}
+
+pub fn to_mermaid(rules: &[BagRule], options: &ExportOptions) -> Result<String, UnknownBag> {
+    let subgraph = Subgraph::new(rules, options)?;
+    let mut mermaid = String::from("flowchart TD\n");
+    for node in subgraph.nodes() {
+        let id = label(subgraph.graph.id(node)).replace("\\\"", "#quot;");
+        mermaid += &format!("    n{}[\"{}\"]\n", node, id);
+    }
+    for (from, count, to) in subgraph.edges() {
+        mermaid += &format!("    n{} -->|{}| n{}\n", from, count, to);
+    }
+    if subgraph.roles.iter().any(|&role| role != Role::Other) {
+        mermaid += "    classDef focus fill:#ffd700\n";
+        mermaid += "    classDef content fill:#add8e6\n";
+        mermaid += "    classDef container fill:#ffb6c1\n";
+        for node in subgraph.nodes() {
+            let class = match subgraph.roles[node] {
+                Role::Focus => "focus",
+                Role::Content => "content",
+                Role::Container => "container",
+                Role::Other => continue,
+            };
+            mermaid += &format!("    class n{} {}\n", node, class);
+        }
+    }
+    Ok(mermaid)
+}
+
+#[test]
+fn export_test() {
+    use crate::parser::parse_rules;
+
+    let rules = parse_rules(&[
+        "light red bags contain 1 bright white bag.",
+        "bright white bags contain 2 shiny gold bags.",
+        "shiny gold bags contain 3 dark olive bags.",
+        "dark olive bags contain 4 faded blue bags.",
+        "faded blue bags contain no other bags.",
+    ]);
+    let options = ExportOptions {
+        highlight: Some(("shiny", "gold").into()),
+        depth: Some(1),
+    };
+    assert_eq!(
+        to_dot(&rules, &options).unwrap(),
+        concat!(
+            "digraph bags {\n",
+            "    \"bright white\" [style=filled, fillcolor=lightpink];\n",
+            "    \"shiny gold\" [style=filled, fillcolor=gold];\n",
+            "    \"dark olive\" [style=filled, fillcolor=lightblue];\n",
+            "    \"bright white\" -> \"shiny gold\" [label=\"2\"];\n",
+            "    \"shiny gold\" -> \"dark olive\" [label=\"3\"];\n",
+            "}\n",
+        )
+    );
+    assert_eq!(
+        to_mermaid(&rules, &ExportOptions::default()).unwrap(),
+        concat!(
+            "flowchart TD\n",
+            "    n0[\"light red\"]\n",
+            "    n1[\"bright white\"]\n",
+            "    n2[\"shiny gold\"]\n",
+            "    n3[\"dark olive\"]\n",
+            "    n4[\"faded blue\"]\n",
+            "    n0 -->|1| n1\n",
+            "    n1 -->|2| n2\n",
+            "    n2 -->|3| n3\n",
+            "    n3 -->|4| n4\n",
+        )
+    );
+
+    let missing = ExportOptions {
+        highlight: Some(("muted", "yellow").into()),
+        depth: None,
+    };
+    assert_eq!(
+        to_dot(&rules, &missing),
+        Err(UnknownBag(("muted", "yellow").into()))
+    );
+
+    // bags sharing a container are neither contents nor containers of each other
+    let siblings = parse_rules(&[
+        "light red bags contain 1 shiny gold bag, 2 faded blue bags.",
+        "shiny gold bags contain no other bags.",
+        "faded blue bags contain no other bags.",
+    ]);
+    let options = ExportOptions {
+        highlight: Some(("shiny", "gold").into()),
+        depth: Some(2),
+    };
+    assert_eq!(
+        to_mermaid(&siblings, &options).unwrap(),
+        concat!(
+            "flowchart TD\n",
+            "    n0[\"light red\"]\n",
+            "    n1[\"shiny gold\"]\n",
+            "    n0 -->|1| n1\n",
+            "    classDef focus fill:#ffd700\n",
+            "    classDef content fill:#add8e6\n",
+            "    classDef container fill:#ffb6c1\n",
+            "    class n0 container\n",
+            "    class n1 focus\n",
+        )
+    );
+}
@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+
+use crate::parser::{BagId, BagRule};
+
+pub type NodeIdx = usize;
+
+// directed graph of bag rules, edges point from a bag to the bags it contains.
+// the graph interns every bag id into a node index, queries take and return
+// node indices so bag names are only compared while building it
+#[derive(Debug, Default)]
+pub struct BagGraph {
+    ids: Vec<BagId>,
+    nodes: HashMap<BagId, NodeIdx>,
+    // (count, contained bag) for every node
+    contents: Vec<Vec<(usize, NodeIdx)>>,
+    // (count, containing bag) for every node
+    containers: Vec<Vec<(usize, NodeIdx)>>,
+}
+
+impl BagGraph {
+    fn add_node(&mut self, id: &BagId) -> NodeIdx {
+        if let Some(&idx) = self.nodes.get(id) {
+            return idx;
+        }
+        self.ids.push(id.clone());
+        self.contents.push(vec![]);
+        self.containers.push(vec![]);
+        self.nodes.insert(id.clone(), self.ids.len() - 1);
+        self.ids.len() - 1
+    }
+
+    pub fn node(&self, id: &BagId) -> Option<NodeIdx> {
+        self.nodes.get(id).copied()
+    }
+
+    pub fn id(&self, node: NodeIdx) -> &BagId {
+        &self.ids[node]
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn contents(&self, node: NodeIdx) -> &[(usize, NodeIdx)] {
+        &self.contents[node]
+    }
+
+    pub fn containers(&self, node: NodeIdx) -> &[(usize, NodeIdx)] {
+        &self.containers[node]
+    }
+
+    fn reachable<'a, F>(&'a self, from: NodeIdx, edges: F) -> Vec<NodeIdx>
+    where
+        F: Fn(NodeIdx) -> &'a [(usize, NodeIdx)],
+    {
+        let mut visited = vec![false; self.len()];
+        let mut stack = vec![from];
+        let mut found = vec![];
+        visited[from] = true;
+        while let Some(node) = stack.pop() {
+            for &(_, next) in edges(node) {
+                if !visited[next] {
+                    visited[next] = true;
+                    found.push(next);
+                    stack.push(next);
+                }
+            }
+        }
+        found
+    }
+
+    // every bag that can eventually contain the given one
+    pub fn ancestors(&self, node: NodeIdx) -> Vec<NodeIdx> {
+        self.reachable(node, |n| self.containers(n))
+    }
+
+    // every bag that can eventually be found inside the given one
+    pub fn descendants(&self, node: NodeIdx) -> Vec<NodeIdx> {
+        self.reachable(node, |n| self.contents(n))
+    }
+}
+
+impl From<&[BagRule]> for BagGraph {
+    fn from(rules: &[BagRule]) -> Self {
+        let mut graph = BagGraph::default();
+        for rule in rules {
+            let bag = graph.add_node(&rule.bag_id);
+            for (count, id) in rule.contents.iter() {
+                let inner = graph.add_node(id);
+                graph.contents[bag].push((*count as usize, inner));
+                graph.containers[inner].push((*count as usize, bag));
+            }
+        }
+        graph
+    }
+}
+
+#[test]
+fn graph_test() {
+    use crate::parser::parse_rules;
+
+    let rules = parse_rules(&[
+        "light red bags contain 1 bright white bag, 2 muted yellow bags.",
+        "bright white bags contain 1 shiny gold bag.",
+        "muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.",
+        "shiny gold bags contain 1 dark olive bag.",
+    ]);
+    let graph = BagGraph::from(rules.as_slice());
+
+    let names = |nodes: Vec<NodeIdx>| -> Vec<&str> {
+        let mut names: Vec<&str> = nodes.into_iter().map(|n| graph.id(n).name()).collect();
+        names.sort_unstable();
+        names
+    };
+    let gold = graph.node(&("shiny", "gold").into()).unwrap();
+    assert_eq!(
+        names(graph.ancestors(gold)),
+        vec!["bright white", "light red", "muted yellow"]
+    );
+    assert_eq!(names(graph.descendants(gold)), vec!["dark olive"]);
+    assert_eq!(graph.len(), 6);
+}
@@ -1,63 +1,99 @@
-use std::fs::read_to_string;
+use std::{env, fs::read_to_string};
 
-use parser::{bag_rule, BagId, BagRule};
+use export::{to_dot, to_mermaid, ExportOptions};
+use graph::NodeIdx;
+use parser::{bag_id, bag_rule, BagRule};
+use queries::{all_chains, leaves, longest_chain, roots, shortest_chain};
+use totals::total_contents;
+use validation::{validate, ValidGraph};
 
+mod export;
+mod graph;
 mod parser;
+mod queries;
+mod totals;
+mod validation;
 
-fn all_that_contain(rules: &Vec<BagRule>, initial: &BagId) -> Vec<BagId> {
-    let mut stack: Vec<BagId> = vec![initial.clone()];
-    let mut stack_idx = 0;
-    while stack_idx != stack.len() {
-        let current_id: BagId = stack.get(stack_idx).unwrap().clone();
-        let mut parent_bag_ids: Vec<BagId> = rules
-            .iter()
-            .filter(|rule| {
-                rule.contents
-                    .iter()
-                    .find(|(_, id)| id == &current_id)
-                    .is_some()
-            })
-            .map(|rule| rule.bag_id.clone())
-            .collect();
+fn part_1(graph: &ValidGraph, bag: NodeIdx) -> usize {
+    graph.ancestors(bag).len()
+}
+
+fn part_2(graph: &ValidGraph, bag: NodeIdx) -> u64 {
+    let totals = total_contents(graph).unwrap_or_else(|err| panic!("{}", err));
+    totals[bag]
+}
 
-        stack.append(&mut parent_bag_ids);
-        stack_idx += 1;
+// a whole argument naming a bag some rule mentions
+fn arg_bag(graph: &ValidGraph, args: &[String], idx: usize) -> Option<NodeIdx> {
+    match bag_id(args.get(idx)?) {
+        Ok(("", bag)) => graph.node(&bag),
+        _ => None,
     }
-    stack.sort();
-    stack.dedup();
-    stack.into_iter().filter(|id| id != initial).collect()
 }
 
-fn all_contained_in(rules: &Vec<BagRule>, initial: &BagId) -> Vec<(usize, BagId)> {
-    let mut stack: Vec<(usize, BagId)> = vec![(1, initial.clone())];
-    let mut stack_idx = 0;
-    while stack_idx != stack.len() {
-        let current_count = stack.get(stack_idx).unwrap().clone();
-        let current_rule = rules
-            .iter()
-            .find(|&rule| rule.bag_id == current_count.1)
-            .unwrap()
-            .clone();
-        let mut to_append = current_rule
-            .contents
-            .iter()
-            .map(|(n, id)| (*n as usize * current_count.0, id.clone()))
-            .collect();
-        stack.append(&mut to_append);
-        stack_idx += 1;
+fn usage() {
+    eprintln!("usage: day7 [<bag>]");
+    eprintln!("       day7 containers|contents <bag>");
+    eprintln!("       day7 shortest|longest|paths <from bag> <to bag>");
+    eprintln!("       day7 roots|leaves");
+    eprintln!("       day7 dot|mermaid [<highlighted bag> [<depth>]]");
+}
+
+fn print_bags(graph: &ValidGraph, bags: &[NodeIdx]) {
+    for &bag in bags {
+        println!("{}", graph.id(bag));
     }
-    stack.into_iter().filter(|(_, id)| id != initial).collect()
 }
 
-fn part_1(rules: &Vec<BagRule>) -> usize {
-    all_that_contain(&rules, &("shiny", "gold").into()).len()
+fn print_chain(graph: &ValidGraph, chain: Option<Vec<NodeIdx>>) {
+    match chain {
+        Some(chain) => {
+            let chain: Vec<String> = chain.iter().map(|&n| graph.id(n).to_string()).collect();
+            println!("{}", chain.join(" -> "));
+        }
+        None => println!("no chain"),
+    }
 }
 
-fn part_2(rules: &Vec<BagRule>) -> usize {
-    all_contained_in(&rules, &("shiny", "gold").into())
-        .iter()
-        .map(|&(c, _)| c as usize)
-        .sum()
+// None when the arguments don't make a query
+fn query(graph: &ValidGraph, args: &[String]) -> Option<()> {
+    let bag = |idx| arg_bag(graph, args, idx);
+    match args.first().map(String::as_str) {
+        Some("containers") => print_bags(graph, &graph.ancestors(bag(1)?)),
+        Some("contents") => print_bags(graph, &graph.descendants(bag(1)?)),
+        Some("shortest") => print_chain(graph, shortest_chain(graph, bag(1)?, bag(2)?)),
+        Some("longest") => print_chain(graph, longest_chain(graph, bag(1)?, bag(2)?)),
+        Some("paths") => {
+            let chains =
+                all_chains(graph, bag(1)?, bag(2)?).unwrap_or_else(|err| panic!("{}", err));
+            for chain in chains {
+                print!("{} x ", chain.multiplicity);
+                print_chain(graph, Some(chain.bags));
+            }
+        }
+        Some("roots") => print_bags(graph, &roots(graph)),
+        Some("leaves") => print_bags(graph, &leaves(graph)),
+        // part 1 and 2, for shiny gold unless another bag is given
+        _ => {
+            let bag = match args.first() {
+                Some(_) => bag(0)?,
+                None => graph.node(&("shiny", "gold").into())?,
+            };
+            println!("Part 1: {}", part_1(graph, bag));
+            println!("Part 2: {}", part_2(graph, bag));
+        }
+    }
+    Some(())
+}
+
+// dot|mermaid [highlighted bag] [depth]
+fn export_options(args: &[String]) -> ExportOptions {
+    ExportOptions {
+        highlight: args
+            .first()
+            .map(|arg| bag_id(arg).expect("highlighted bag id").1),
+        depth: args.get(1).map(|arg| arg.parse().expect("depth")),
+    }
 }
 
 fn main() {
@@ -66,6 +102,26 @@ fn main() {
         .lines()
         .map(|line| bag_rule(line).unwrap().1)
         .collect();
-    println!("Part 1: {}", part_1(&rules));
-    println!("Part 2: {}", part_2(&rules));
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("dot") => print!(
+            "{}",
+            to_dot(&rules, &export_options(&args[1..])).unwrap_or_else(|err| panic!("{}", err))
+        ),
+        Some("mermaid") => print!(
+            "{}",
+            to_mermaid(&rules, &export_options(&args[1..])).unwrap_or_else(|err| panic!("{}", err))
+        ),
+        _ => {
+            let graph = validate(&rules).unwrap_or_else(|errors| {
+                for error in errors {
+                    eprintln!("{}", error);
+                }
+                panic!("invalid bag rules");
+            });
+            if query(&graph, &args).is_none() {
+                usage();
+            }
+        }
+    }
 }
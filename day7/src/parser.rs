@@ -1,31 +1,49 @@
-use std::fmt::Debug;
+use std::{fmt, rc::Rc};
 
 use nom::{
     branch::alt,
-    bytes::complete::tag,
-    character::complete::alpha1,
+    bytes::complete::{tag, take_while1},
     character::complete::space0,
     character::complete::{digit1, space1},
     combinator::map,
-    combinator::map_res,
+    combinator::{map_res, recognize, verify},
     multi::separated_list1,
     sequence::{delimited, terminated},
     sequence::{separated_pair, tuple},
     IResult,
 };
 
-#[derive(PartialEq, Eq, Clone, Hash, Ord, PartialOrd)]
-pub struct BagId(pub String, pub String);
+// bag descriptor with its words separated by single spaces. clones share the
+// string, and BagGraph interns ids into node indices for everything past parsing
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BagId(Rc<str>);
 
-impl Debug for BagId {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} {}", self.0, self.1)
+impl BagId {
+    pub fn new(descriptor: &str) -> Self {
+        let descriptor = descriptor.split_whitespace().collect::<Vec<_>>().join(" ");
+        BagId(descriptor.into())
+    }
+
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for BagId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl From<&str> for BagId {
+    fn from(descriptor: &str) -> Self {
+        BagId::new(descriptor)
     }
 }
 
 impl From<(&str, &str)> for BagId {
     fn from((kind, color): (&str, &str)) -> Self {
-        BagId(kind.into(), color.into())
+        BagId::new(&format!("{} {}", kind, color))
     }
 }
 
@@ -43,10 +61,18 @@ fn u8_number(input: &str) -> IResult<&str, u8> {
     map_res(digit1, u8_parser)(input)
 }
 
-fn bag_id(input: &str) -> IResult<&str, BagId> {
+fn descriptor_word(input: &str) -> IResult<&str, &str> {
+    verify(
+        take_while1(|c: char| c.is_alphanumeric() || c == '-'),
+        |word: &str| word != "bag" && word != "bags",
+    )(input)
+}
+
+// any number of words made of letters, digits and hyphens, up to "bag(s)"
+pub fn bag_id(input: &str) -> IResult<&str, BagId> {
     map(
-        separated_pair(alpha1, space1, alpha1),
-        |(kind, color): (&str, &str)| -> BagId { BagId(kind.into(), color.into()) },
+        recognize(separated_list1(space1, descriptor_word)),
+        BagId::new,
     )(input)
 }
 
@@ -83,6 +109,28 @@ pub fn bag_rule(input: &str) -> IResult<&str, BagRule> {
     )(input)
 }
 
+// one rule per line, for test fixtures
+#[cfg(test)]
+pub fn parse_rules(lines: &[&str]) -> Vec<BagRule> {
+    lines.iter().map(|line| bag_rule(line).unwrap().1).collect()
+}
+
+// "level 0" holds `count` of "level 1" and so on, down to the empty bag at `levels`
+#[cfg(test)]
+pub fn chain_rules(levels: usize, count: u8) -> Vec<BagRule> {
+    let level = |level: usize| BagId::from(("level", level.to_string().as_str()));
+    (0..levels)
+        .map(|idx| BagRule {
+            bag_id: level(idx),
+            contents: vec![(count, level(idx + 1))],
+        })
+        .chain(std::iter::once(BagRule {
+            bag_id: level(levels),
+            contents: vec![],
+        }))
+        .collect()
+}
+
 #[test]
 fn test_parser() {
     assert_eq!(
@@ -95,3 +143,17 @@ fn test_parser() {
         },
     );
 }
+
+#[test]
+fn test_multi_word_parser() {
+    let rule = bag_rule("light sky blue bags contain 1 neon-green bag, 12 x 2 bags.")
+        .unwrap()
+        .1;
+    assert_eq!(rule.bag_id, BagId::new("light sky blue"));
+    assert_eq!(
+        rule.contents,
+        vec![(1, "neon-green".into()), (12, "x 2".into())]
+    );
+    assert_eq!(BagId::new("light  sky blue").name(), "light sky blue");
+    assert_eq!(BagId::new("neon-green").to_string(), "neon-green");
+}
@@ -0,0 +1,214 @@
+use std::{collections::VecDeque, fmt};
+
+use crate::{graph::NodeIdx, parser::BagId, validation::ValidGraph};
+
+// queries take and return node indices, BagGraph maps them to bag ids
+
+#[derive(Debug, PartialEq)]
+pub struct Chain {
+    pub bags: Vec<NodeIdx>,
+    // how many of the last bags end up inside the first one along this chain
+    pub multiplicity: u64,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ChainOverflow {
+    pub from: BagId,
+    pub to: BagId,
+}
+
+impl fmt::Display for ChainOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "bags along a chain from {} to {} overflow u64",
+            self.from, self.to
+        )
+    }
+}
+
+// bags nothing contains
+pub fn roots(graph: &ValidGraph) -> Vec<NodeIdx> {
+    (0..graph.len())
+        .filter(|&node| graph.containers(node).is_empty())
+        .collect()
+}
+
+// bags that contain nothing
+pub fn leaves(graph: &ValidGraph) -> Vec<NodeIdx> {
+    (0..graph.len())
+        .filter(|&node| graph.contents(node).is_empty())
+        .collect()
+}
+
+pub fn shortest_chain(graph: &ValidGraph, from: NodeIdx, to: NodeIdx) -> Option<Vec<NodeIdx>> {
+    let mut previous: Vec<Option<NodeIdx>> = vec![None; graph.len()];
+    let mut queue = VecDeque::from(vec![from]);
+    while let Some(node) = queue.pop_front() {
+        if node == to {
+            let mut chain = vec![to];
+            while let Some(prev) = previous[*chain.last().unwrap()] {
+                chain.push(prev);
+            }
+            chain.reverse();
+            return Some(chain);
+        }
+        for &(_, inner) in graph.contents(node) {
+            if inner != from && previous[inner].is_none() {
+                previous[inner] = Some(node);
+                queue.push_back(inner);
+            }
+        }
+    }
+    None
+}
+
+// longest distance to `to` and the next bag on the way for every bag reachable
+// from `from`. rules are acyclic, so finishing bags in depth first post order
+// sees every bag after all the bags inside it
+fn longest_to(graph: &ValidGraph, from: NodeIdx, to: NodeIdx) -> Vec<Option<(usize, NodeIdx)>> {
+    let mut best = vec![None; graph.len()];
+    let mut visited = vec![false; graph.len()];
+    // (node, index of the next edge to follow)
+    let mut stack: Vec<(NodeIdx, usize)> = vec![(from, 0)];
+    visited[from] = true;
+    while let Some(&(node, edge_idx)) = stack.last() {
+        if node == to {
+            best[to] = Some((0, to));
+            stack.pop();
+            continue;
+        }
+        match graph.contents(node).get(edge_idx) {
+            Some(&(_, inner)) => {
+                stack.last_mut().unwrap().1 += 1;
+                if !visited[inner] {
+                    visited[inner] = true;
+                    stack.push((inner, 0));
+                }
+            }
+            None => {
+                best[node] = graph
+                    .contents(node)
+                    .iter()
+                    .filter_map(|&(_, inner)| best[inner].map(|(len, _)| (len + 1, inner)))
+                    .max_by_key(|&(len, _)| len);
+                stack.pop();
+            }
+        }
+    }
+    best
+}
+
+pub fn longest_chain(graph: &ValidGraph, from: NodeIdx, to: NodeIdx) -> Option<Vec<NodeIdx>> {
+    let best = longest_to(graph, from, to);
+    best[from]?;
+    let mut chain = vec![from];
+    while let Some(&node) = chain.last().filter(|&&node| node != to) {
+        chain.push(best[node].unwrap().1);
+    }
+    Some(chain)
+}
+
+// only bags that can reach `to` are explored
+pub fn all_chains(
+    graph: &ValidGraph,
+    from: NodeIdx,
+    to: NodeIdx,
+) -> Result<Vec<Chain>, ChainOverflow> {
+    let mut reaches_to = vec![false; graph.len()];
+    reaches_to[to] = true;
+    for node in graph.ancestors(to) {
+        reaches_to[node] = true;
+    }
+    let mut chains = vec![];
+    // (path so far, multiplicity so far)
+    let mut stack: Vec<(Vec<NodeIdx>, u64)> = vec![(vec![from], 1)];
+    while let Some((path, multiplicity)) = stack.pop() {
+        let node = *path.last().unwrap();
+        if node == to && path.len() > 1 {
+            chains.push(Chain {
+                bags: path,
+                multiplicity,
+            });
+            continue;
+        }
+        for &(count, inner) in graph.contents(node).iter().rev() {
+            if !reaches_to[inner] {
+                continue;
+            }
+            let multiplicity = multiplicity
+                .checked_mul(count as u64)
+                .ok_or(ChainOverflow {
+                    from: graph.id(from).clone(),
+                    to: graph.id(to).clone(),
+                })?;
+            let mut next_path = path.clone();
+            next_path.push(inner);
+            stack.push((next_path, multiplicity));
+        }
+    }
+    Ok(chains)
+}
+
+#[test]
+fn queries_test() {
+    use crate::{
+        parser::{chain_rules, parse_rules},
+        validation::validate,
+    };
+
+    let rules = parse_rules(&[
+        "light red bags contain 1 bright white bag, 2 muted yellow bags.",
+        "dark orange bags contain 3 bright white bags, 4 muted yellow bags.",
+        "bright white bags contain 1 shiny gold bag.",
+        "muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.",
+        "shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.",
+        "dark olive bags contain 3 faded blue bags, 4 dotted black bags.",
+        "vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.",
+        "faded blue bags contain no other bags.",
+        "dotted black bags contain no other bags.",
+    ]);
+    let graph = validate(&rules).unwrap();
+    let node = |name: &str| graph.node(&name.into()).unwrap();
+    let (red, blue) = (node("light red"), node("faded blue"));
+
+    assert_eq!(roots(&graph), vec![red, node("dark orange")]);
+    assert_eq!(leaves(&graph), vec![blue, node("dotted black")]);
+    assert_eq!(
+        shortest_chain(&graph, red, blue),
+        Some(vec![red, node("muted yellow"), blue])
+    );
+    assert_eq!(longest_chain(&graph, red, blue).unwrap().len(), 5);
+    assert_eq!(shortest_chain(&graph, blue, red), None);
+
+    let chains = all_chains(&graph, red, blue).unwrap();
+    assert_eq!(chains.len(), 5);
+    assert_eq!(chains[0].multiplicity, 3);
+    assert_eq!(
+        chains.iter().map(|chain| chain.multiplicity).sum::<u64>(),
+        (3 + 10) + 2 * 9 + 2 * 2 * (3 + 10)
+    );
+    assert!(all_chains(&graph, blue, red).unwrap().is_empty());
+
+    // 9 levels of 255 bags each overflow u64 along the only chain
+    let deep = validate(&chain_rules(9, 255)).unwrap();
+    let (top, bottom) = (deep.node(&"level 0".into()), deep.node(&"level 9".into()));
+    assert_eq!(
+        all_chains(&deep, top.unwrap(), bottom.unwrap()),
+        Err(ChainOverflow {
+            from: "level 0".into(),
+            to: "level 9".into()
+        })
+    );
+
+    // deeper than the call stack would allow for a recursive search
+    let deep = validate(&chain_rules(200_000, 1)).unwrap();
+    let (top, bottom) = (
+        deep.node(&"level 0".into()),
+        deep.node(&"level 200000".into()),
+    );
+    assert_eq!(
+        longest_chain(&deep, top.unwrap(), bottom.unwrap()).map(|chain| chain.len()),
+        Some(200_001)
+    );
+}
@@ -0,0 +1,71 @@
+use std::fmt;
+
+use crate::{parser::BagId, validation::ValidGraph};
+
+#[derive(Debug, PartialEq)]
+pub struct TotalOverflow(pub BagId);
+
+impl fmt::Display for TotalOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "total bags inside {} overflows u64", self.0)
+    }
+}
+
+// how many bags end up inside every bag indexed by node, computed leaves first
+// so every bag is visited once no matter how many paths lead to it
+pub fn total_contents(graph: &ValidGraph) -> Result<Vec<u64>, TotalOverflow> {
+    let mut totals: Vec<u64> = vec![0; graph.len()];
+    let mut remaining: Vec<usize> = (0..graph.len())
+        .map(|node| graph.contents(node).len())
+        .collect();
+    let mut ready: Vec<usize> = (0..graph.len())
+        .filter(|&node| remaining[node] == 0)
+        .collect();
+    while let Some(node) = ready.pop() {
+        let total = graph
+            .contents(node)
+            .iter()
+            .try_fold(0u64, |acc, &(count, inner)| {
+                totals[inner]
+                    .checked_add(1)
+                    .and_then(|with_inner| with_inner.checked_mul(count as u64))
+                    .and_then(|inside| acc.checked_add(inside))
+            })
+            .ok_or_else(|| TotalOverflow(graph.id(node).clone()))?;
+        totals[node] = total;
+        for &(_, container) in graph.containers(node) {
+            remaining[container] -= 1;
+            if remaining[container] == 0 {
+                ready.push(container);
+            }
+        }
+    }
+    Ok(totals)
+}
+
+#[test]
+fn total_contents_test() {
+    use crate::{
+        parser::{chain_rules, parse_rules},
+        validation::validate,
+    };
+
+    let rules = parse_rules(&[
+        "shiny gold bags contain 2 dark red bags.",
+        "dark red bags contain 2 dark orange bags.",
+        "dark orange bags contain 2 dark yellow bags.",
+        "dark yellow bags contain 2 dark green bags.",
+        "dark green bags contain 2 dark blue bags.",
+        "dark blue bags contain 2 dark violet bags.",
+        "dark violet bags contain no other bags.",
+    ]);
+    let graph = validate(&rules).unwrap();
+    let totals = total_contents(&graph).unwrap();
+    let total = |name: &str| totals[graph.node(&name.into()).unwrap()];
+    assert_eq!(total("shiny gold"), 126);
+    assert_eq!(total("dark blue"), 2);
+
+    // 64 levels of 255 bags each do not fit into u64
+    let deep = chain_rules(64, 255);
+    assert!(total_contents(&validate(&deep).unwrap()).is_err());
+}
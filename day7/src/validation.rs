@@ -0,0 +1,146 @@
+use std::{collections::HashSet, fmt, ops::Deref};
+
+use crate::{
+    graph::{BagGraph, NodeIdx},
+    parser::{BagId, BagRule},
+};
+
+#[derive(Debug, PartialEq)]
+pub enum ValidationError {
+    DuplicateRule(BagId),
+    UndefinedBag { container: BagId, missing: BagId },
+    // bags along the cycle, the first one is repeated at the end
+    Cycle(Vec<BagId>),
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::DuplicateRule(id) => write!(f, "duplicate rule for {}", id),
+            ValidationError::UndefinedBag { container, missing } => {
+                write!(f, "{} contains {} which has no rule", container, missing)
+            }
+            ValidationError::Cycle(path) => {
+                let path: Vec<String> = path.iter().map(BagId::to_string).collect();
+                write!(f, "cycle {}", path.join(" -> "))
+            }
+        }
+    }
+}
+
+// graph of rules that passed validation, safe to run counting queries on
+#[derive(Debug)]
+pub struct ValidGraph(BagGraph);
+
+impl Deref for ValidGraph {
+    type Target = BagGraph;
+
+    fn deref(&self) -> &BagGraph {
+        &self.0
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Visit {
+    New,
+    InProgress,
+    Done,
+}
+
+fn cycles(graph: &BagGraph) -> Vec<Vec<BagId>> {
+    let mut state = vec![Visit::New; graph.len()];
+    let mut cycles = vec![];
+    for root in 0..graph.len() {
+        if state[root] != Visit::New {
+            continue;
+        }
+        // (node, index of the next edge to follow)
+        let mut path: Vec<(NodeIdx, usize)> = vec![(root, 0)];
+        state[root] = Visit::InProgress;
+        while let Some(&(node, edge_idx)) = path.last() {
+            match graph.contents(node).get(edge_idx) {
+                Some(&(_, next)) => {
+                    path.last_mut().unwrap().1 += 1;
+                    match state[next] {
+                        Visit::New => {
+                            state[next] = Visit::InProgress;
+                            path.push((next, 0));
+                        }
+                        Visit::InProgress => {
+                            let start = path.iter().position(|&(n, _)| n == next).unwrap();
+                            let mut cycle: Vec<BagId> = path[start..]
+                                .iter()
+                                .map(|&(n, _)| graph.id(n).clone())
+                                .collect();
+                            cycle.push(graph.id(next).clone());
+                            cycles.push(cycle);
+                        }
+                        Visit::Done => {}
+                    }
+                }
+                None => {
+                    state[node] = Visit::Done;
+                    path.pop();
+                }
+            }
+        }
+    }
+    cycles
+}
+
+pub fn validate(rules: &[BagRule]) -> Result<ValidGraph, Vec<ValidationError>> {
+    let mut errors = vec![];
+    let mut defined = HashSet::new();
+    for rule in rules {
+        if !defined.insert(&rule.bag_id) {
+            errors.push(ValidationError::DuplicateRule(rule.bag_id.clone()));
+        }
+    }
+    for rule in rules {
+        for (_, id) in rule.contents.iter() {
+            if !defined.contains(id) {
+                errors.push(ValidationError::UndefinedBag {
+                    container: rule.bag_id.clone(),
+                    missing: id.clone(),
+                });
+            }
+        }
+    }
+    let graph = BagGraph::from(rules);
+    errors.extend(cycles(&graph).into_iter().map(ValidationError::Cycle));
+    if errors.is_empty() {
+        Ok(ValidGraph(graph))
+    } else {
+        Err(errors)
+    }
+}
+
+#[test]
+fn validation_test() {
+    use crate::parser::parse_rules;
+
+    let rules = parse_rules(&[
+        "light red bags contain 1 bright white bag.",
+        "bright white bags contain 1 shiny gold bag, 2 faded blue bags.",
+        "shiny gold bags contain 3 light red bags.",
+        "shiny gold bags contain no other bags.",
+    ]);
+    assert_eq!(
+        validate(&rules).unwrap_err(),
+        vec![
+            ValidationError::DuplicateRule(("shiny", "gold").into()),
+            ValidationError::UndefinedBag {
+                container: ("bright", "white").into(),
+                missing: ("faded", "blue").into()
+            },
+            ValidationError::Cycle(vec![
+                ("light", "red").into(),
+                ("bright", "white").into(),
+                ("shiny", "gold").into(),
+                ("light", "red").into(),
+            ]),
+        ]
+    );
+    assert!(validate(&rules[1..2]).is_err());
+    assert!(validate(&rules[3..]).is_ok());
+}
@@ -0,0 +1,228 @@
+use std::fmt::Write;
+
+use crate::{
+    cfg::Cfg,
+    isa::{Ins, Opcode},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Exit {
+    Block(usize),
+    End,
+    OutOfBounds(i64),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BasicBlock {
+    pub start: usize,
+    // exclusive
+    pub end: usize,
+    pub exit: Exit,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Analysis {
+    pub blocks: Vec<BasicBlock>,
+    pub unreachable: Vec<usize>,
+    // (pc, target) of jumps leaving the program anywhere but its end
+    pub out_of_bounds: Vec<(usize, i64)>,
+    // cycles entered when running from pc 0, every instruction has a single
+    // successor so once execution gets there it never leaves
+    pub certain_loops: Vec<Vec<usize>>,
+    // cycles in unreachable code, they loop forever if anything jumps in
+    pub possible_loops: Vec<Vec<usize>>,
+}
+
+fn in_program(cfg: &Cfg, target: i64) -> Option<usize> {
+    if target >= 0 && (target as usize) < cfg.end() {
+        Some(target as usize)
+    } else {
+        None
+    }
+}
+
+fn basic_blocks(program: &[Ins], cfg: &Cfg) -> Vec<BasicBlock> {
+    let mut leader = vec![false; program.len()];
+    if let Some(first) = leader.first_mut() {
+        *first = true;
+    }
+    for (pc, ins) in program.iter().enumerate() {
+        if ins.op == Opcode::Jmp {
+            if let Some(target) = in_program(cfg, cfg.successors[pc]) {
+                leader[target] = true;
+            }
+            if let Some(next) = leader.get_mut(pc + 1) {
+                *next = true;
+            }
+        }
+    }
+    let starts: Vec<usize> = (0..program.len()).filter(|&pc| leader[pc]).collect();
+    let mut block_of = vec![0; program.len()];
+    for (block, &start) in starts.iter().enumerate() {
+        let end = starts.get(block + 1).copied().unwrap_or(program.len());
+        block_of[start..end].fill(block);
+    }
+    starts
+        .iter()
+        .enumerate()
+        .map(|(block, &start)| {
+            let end = starts.get(block + 1).copied().unwrap_or(program.len());
+            let target = cfg.successors[end - 1];
+            let exit = match in_program(cfg, target) {
+                Some(pc) => Exit::Block(block_of[pc]),
+                None if target as usize == cfg.end() => Exit::End,
+                None => Exit::OutOfBounds(target),
+            };
+            BasicBlock { start, end, exit }
+        })
+        .collect()
+}
+
+// every instruction has exactly one successor, so walking from each
+// instruction finds every cycle in linear time
+fn cycles(cfg: &Cfg) -> Vec<Vec<usize>> {
+    let mut walk_of: Vec<Option<usize>> = vec![None; cfg.end()];
+    let mut cycles = vec![];
+    for start in 0..cfg.end() {
+        let mut pc = start;
+        let mut walk = vec![];
+        loop {
+            if let Some(owner) = walk_of[pc] {
+                // only a walk that runs into itself closes a new cycle
+                if owner == start {
+                    let cycle_start = walk.iter().position(|&p| p == pc).unwrap();
+                    cycles.push(walk[cycle_start..].to_vec());
+                }
+                break;
+            }
+            walk_of[pc] = Some(start);
+            walk.push(pc);
+            match in_program(cfg, cfg.successors[pc]) {
+                Some(next) => pc = next,
+                None => break,
+            }
+        }
+    }
+    cycles
+}
+
+pub fn analyse(program: &[Ins]) -> Analysis {
+    let cfg = Cfg::new(program);
+    let mut reachable = vec![false; program.len()];
+    for pc in cfg.path_from_start() {
+        reachable[pc] = true;
+    }
+    let (certain_loops, possible_loops) = cycles(&cfg)
+        .into_iter()
+        .partition(|cycle| reachable[cycle[0]]);
+    Analysis {
+        blocks: basic_blocks(program, &cfg),
+        unreachable: (0..program.len()).filter(|&pc| !reachable[pc]).collect(),
+        out_of_bounds: cfg
+            .successors
+            .iter()
+            .enumerate()
+            .filter(|&(_, &target)| target < 0 || target as usize > cfg.end())
+            .map(|(pc, &target)| (pc, target))
+            .collect(),
+        certain_loops,
+        possible_loops,
+    }
+}
+
+impl Analysis {
+    pub fn to_dot(&self, program: &[Ins]) -> String {
+        let unreachable = |block: &BasicBlock| self.unreachable.binary_search(&block.start).is_ok();
+        let in_loop = |block: &BasicBlock| {
+            self.certain_loops
+                .iter()
+                .chain(self.possible_loops.iter())
+                .any(|cycle| cycle.iter().any(|&pc| pc >= block.start && pc < block.end))
+        };
+        let mut dot = String::from("digraph cfg {\n    node [shape=box, fontname=monospace];\n");
+        dot += "    end [shape=doublecircle];\n";
+        for (idx, block) in self.blocks.iter().enumerate() {
+            let mut label = String::new();
+            for (pc, ins) in program[block.start..block.end].iter().enumerate() {
+                write!(label, "{}: {}\\l", block.start + pc, ins).unwrap();
+            }
+            let style = match (unreachable(block), in_loop(block)) {
+                (true, _) => ", style=dashed, color=gray",
+                (false, true) => ", color=red",
+                (false, false) => "",
+            };
+            writeln!(dot, "    b{} [label=\"{}\"{}];", idx, label, style).unwrap();
+            match block.exit {
+                Exit::Block(next) => writeln!(dot, "    b{} -> b{};", idx, next).unwrap(),
+                Exit::End => writeln!(dot, "    b{} -> end;", idx).unwrap(),
+                Exit::OutOfBounds(target) => {
+                    writeln!(dot, "    oob{} [label=\"{}\", color=red];", idx, target).unwrap();
+                    writeln!(dot, "    b{} -> oob{};", idx, idx).unwrap();
+                }
+            }
+        }
+        dot += "}\n";
+        dot
+    }
+}
+
+#[test]
+fn analysis_test() {
+    use crate::asm::assemble;
+
+    let program = assemble(
+        "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6\njmp -20",
+    )
+    .unwrap();
+    let analysis = analyse(&program);
+    assert_eq!(
+        analysis.blocks,
+        vec![
+            BasicBlock {
+                start: 0,
+                end: 1,
+                exit: Exit::Block(1)
+            },
+            BasicBlock {
+                start: 1,
+                end: 3,
+                exit: Exit::Block(4)
+            },
+            BasicBlock {
+                start: 3,
+                end: 5,
+                exit: Exit::Block(1)
+            },
+            BasicBlock {
+                start: 5,
+                end: 6,
+                exit: Exit::Block(4)
+            },
+            BasicBlock {
+                start: 6,
+                end: 8,
+                exit: Exit::Block(2)
+            },
+            BasicBlock {
+                start: 8,
+                end: 10,
+                exit: Exit::OutOfBounds(-11)
+            },
+        ]
+    );
+    assert_eq!(analysis.unreachable, vec![5, 8, 9]);
+    assert_eq!(analysis.out_of_bounds, vec![(9, -11)]);
+    assert_eq!(analysis.certain_loops, vec![vec![1, 2, 6, 7, 3, 4]]);
+    assert!(analysis.possible_loops.is_empty());
+
+    let analysis = analyse(&assemble("jmp +2\njmp +0\nacc +1").unwrap());
+    assert_eq!(analysis.possible_loops, vec![vec![1]]);
+    assert!(analysis.certain_loops.is_empty());
+
+    let far = assemble("nop +0\njmp +9223372036854775807").unwrap();
+    let analysis = analyse(&far);
+    assert_eq!(analysis.out_of_bounds, vec![(1, i64::MAX)]);
+    assert!(analysis
+        .to_dot(&far)
+        .contains("label=\"9223372036854775807\""));
+}
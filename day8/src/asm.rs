@@ -0,0 +1,117 @@
+use std::fmt;
+
+use crate::{
+    isa::{Ins, Opcode, Operand},
+    machine::REGISTERS,
+};
+
+#[derive(Debug, PartialEq)]
+pub enum AsmErrorKind {
+    UnknownOpcode(String),
+    MissingArgument,
+    InvalidArgument(String),
+    UnknownRegister(String),
+    UnexpectedToken(String),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct AsmError {
+    pub line: usize,
+    pub kind: AsmErrorKind,
+}
+
+impl fmt::Display for AsmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            AsmErrorKind::UnknownOpcode(op) => write!(f, "unknown opcode {}", op),
+            AsmErrorKind::MissingArgument => write!(f, "missing argument"),
+            AsmErrorKind::InvalidArgument(arg) => write!(f, "invalid argument {}", arg),
+            AsmErrorKind::UnknownRegister(name) => write!(f, "unknown register {}", name),
+            AsmErrorKind::UnexpectedToken(token) => write!(f, "unexpected {}", token),
+        }
+    }
+}
+
+fn assemble_line(line: &str) -> Result<Ins, AsmErrorKind> {
+    let mut tokens = line.split_whitespace();
+    let mnemonic = tokens.next().unwrap_or_default();
+    let op = Opcode::from_mnemonic(mnemonic)
+        .ok_or_else(|| AsmErrorKind::UnknownOpcode(mnemonic.into()))?;
+    let arg = match op.operand() {
+        Operand::None => 0,
+        Operand::Register => {
+            let name = tokens.next().ok_or(AsmErrorKind::MissingArgument)?;
+            REGISTERS
+                .iter()
+                .position(|&register| register == name)
+                .ok_or_else(|| AsmErrorKind::UnknownRegister(name.into()))? as i64
+        }
+        Operand::Immediate | Operand::Address => {
+            let arg = tokens.next().ok_or(AsmErrorKind::MissingArgument)?;
+            arg.parse::<i64>()
+                .map_err(|_| AsmErrorKind::InvalidArgument(arg.into()))?
+        }
+    };
+    if let Some(token) = tokens.next() {
+        return Err(AsmErrorKind::UnexpectedToken(token.into()));
+    }
+    Ok(Ins::new(op, arg))
+}
+
+// one instruction per line, blank lines and `#` comments are skipped
+pub fn assemble(source: &str) -> Result<Vec<Ins>, AsmError> {
+    source
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| (idx, line.split('#').next().unwrap_or_default().trim()))
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(idx, line)| {
+            assemble_line(line).map_err(|kind| AsmError {
+                line: idx + 1,
+                kind,
+            })
+        })
+        .collect()
+}
+
+pub fn disassemble(program: &[Ins]) -> String {
+    program.iter().map(|ins| format!("{}\n", ins)).collect()
+}
+
+#[test]
+fn assembler_test() {
+    let source = "nop +0\n\nacc +1 # comment\njmp -2\n";
+    let program = assemble(source).unwrap();
+    assert_eq!(
+        program,
+        vec![
+            Ins::new(Opcode::Nop, 0),
+            Ins::new(Opcode::Acc, 1),
+            Ins::new(Opcode::Jmp, -2)
+        ]
+    );
+    assert_eq!(disassemble(&program), "nop +0\nacc +1\njmp -2\n");
+    assert_eq!(
+        assemble("nop +0\nmul 3").unwrap_err(),
+        AsmError {
+            line: 2,
+            kind: AsmErrorKind::UnknownOpcode("mul".into())
+        }
+    );
+    assert_eq!(
+        assemble("acc x").unwrap_err().kind,
+        AsmErrorKind::InvalidArgument("x".into())
+    );
+
+    let source = "in\nst c\nldm 4\npush\nout\n";
+    assert_eq!(disassemble(&assemble(source).unwrap()), source);
+    assert_eq!(
+        assemble("ld z").unwrap_err().kind,
+        AsmErrorKind::UnknownRegister("z".into())
+    );
+    assert_eq!(
+        assemble("pop a").unwrap_err().kind,
+        AsmErrorKind::UnexpectedToken("a".into())
+    );
+}
@@ -0,0 +1,70 @@
+use crate::isa::{Ins, Opcode};
+
+// where control goes after executing `ins` at `pc`, may be outside the program.
+// a target past i64::MAX is out of bounds anyway, so it saturates there
+pub fn successor(pc: usize, ins: &Ins) -> i64 {
+    match ins.op {
+        Opcode::Jmp => (pc as i64).checked_add(ins.arg).unwrap_or(i64::MAX),
+        _ => pc as i64 + 1,
+    }
+}
+
+// control flow graph, node `program.len()` stands for the program end
+pub struct Cfg {
+    pub successors: Vec<i64>,
+    pub predecessors: Vec<Vec<usize>>,
+}
+
+impl Cfg {
+    pub fn new(program: &[Ins]) -> Self {
+        let end = program.len();
+        let successors: Vec<i64> = program
+            .iter()
+            .enumerate()
+            .map(|(pc, ins)| successor(pc, ins))
+            .collect();
+        let mut predecessors = vec![vec![]; end + 1];
+        for (pc, &next) in successors.iter().enumerate() {
+            if next >= 0 && next as usize <= end {
+                predecessors[next as usize].push(pc);
+            }
+        }
+        Cfg {
+            successors,
+            predecessors,
+        }
+    }
+
+    pub fn end(&self) -> usize {
+        self.successors.len()
+    }
+
+    // instructions from which execution reaches exactly the program end
+    pub fn terminating(&self) -> Vec<bool> {
+        let mut terminating = vec![false; self.end() + 1];
+        let mut stack = vec![self.end()];
+        terminating[self.end()] = true;
+        while let Some(node) = stack.pop() {
+            for &pred in self.predecessors[node].iter() {
+                if !terminating[pred] {
+                    terminating[pred] = true;
+                    stack.push(pred);
+                }
+            }
+        }
+        terminating
+    }
+
+    // instructions executed from pc 0 until the program ends, leaves or loops
+    pub fn path_from_start(&self) -> Vec<usize> {
+        let mut visited = vec![false; self.end()];
+        let mut path = vec![];
+        let mut pc: i64 = 0;
+        while pc >= 0 && (pc as usize) < self.end() && !visited[pc as usize] {
+            visited[pc as usize] = true;
+            path.push(pc as usize);
+            pc = self.successors[pc as usize];
+        }
+        path
+    }
+}
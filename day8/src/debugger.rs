@@ -0,0 +1,359 @@
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{self, BufRead, BufWriter, Write},
+};
+
+use crate::{
+    isa::{Ins, Opcode},
+    machine::Machine,
+    run::{executed, fetch, RunOutcome, Watchdog},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Breakpoint {
+    Pc(usize),
+    Opcode(Opcode),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Step {
+    pub pc: usize,
+    pub ins: Ins,
+    pub acc_before: i64,
+    pub acc_after: i64,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum StopReason {
+    Breakpoint(Breakpoint),
+    AccChanged { from: i64, to: i64 },
+    // the program stopped for the same reason `run` would give
+    Ended(RunOutcome),
+}
+
+pub struct Debugger<'a> {
+    program: &'a [Ins],
+    pub machine: Machine,
+    breakpoints: Vec<Breakpoint>,
+    watch_acc: bool,
+    history: VecDeque<Step>,
+    history_len: usize,
+    trace: Vec<Step>,
+    // set once the program can't go on, cleared by stepping back
+    outcome: Option<RunOutcome>,
+}
+
+impl<'a> Debugger<'a> {
+    pub fn new(program: &'a [Ins], history_len: usize) -> Self {
+        Debugger {
+            program,
+            machine: Machine::default(),
+            breakpoints: vec![],
+            watch_acc: false,
+            history: VecDeque::new(),
+            history_len,
+            trace: vec![],
+            outcome: None,
+        }
+    }
+
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) {
+        self.breakpoints.push(breakpoint);
+    }
+
+    pub fn clear_breakpoints(&mut self) {
+        self.breakpoints.clear();
+    }
+
+    pub fn watch_acc(&mut self, watch: bool) {
+        self.watch_acc = watch;
+    }
+
+    pub fn current(&self) -> Option<&Ins> {
+        self.program.get(self.machine.pc)
+    }
+
+    pub fn outcome(&self) -> Option<RunOutcome> {
+        self.outcome
+    }
+
+    // None when the program has ended, the instruction that faulted isn't a step
+    pub fn step(&mut self) -> Option<Step> {
+        if self.outcome.is_some() {
+            return None;
+        }
+        let pc = self.machine.pc;
+        let ins = match fetch(self.program, pc) {
+            Ok(ins) => ins,
+            Err(outcome) => {
+                self.outcome = Some(outcome);
+                return None;
+            }
+        };
+        let acc_before = self.machine.acc;
+        let result = self.machine.exec(&ins);
+        self.outcome = executed(self.program, pc, ins, result, &self.machine);
+        if result.is_err() {
+            return None;
+        }
+        let step = Step {
+            pc,
+            ins,
+            acc_before,
+            acc_after: self.machine.acc,
+        };
+        if self.history.len() == self.history_len {
+            self.history.pop_front();
+        }
+        if self.history_len > 0 {
+            self.history.push_back(step);
+        }
+        self.trace.push(step);
+        Some(step)
+    }
+
+    // undoes the last step kept in history, only pc and acc are restored
+    // so extended machine state stays as it is
+    pub fn back(&mut self) -> Option<Step> {
+        let step = self.history.pop_back()?;
+        self.outcome = None;
+        self.machine.pc = step.pc;
+        self.machine.acc = step.acc_before;
+        Some(step)
+    }
+
+    pub fn history(&self) -> impl Iterator<Item = &Step> {
+        self.history.iter()
+    }
+
+    fn breakpoint_at(&self, pc: usize) -> Option<Breakpoint> {
+        let ins = self.program.get(pc)?;
+        self.breakpoints
+            .iter()
+            .copied()
+            .find(|&breakpoint| match breakpoint {
+                Breakpoint::Pc(at) => at == pc,
+                Breakpoint::Opcode(op) => op == ins.op,
+            })
+    }
+
+    // always executes at least one instruction, so continuing from a
+    // breakpoint does not stop on it again
+    pub fn run(&mut self) -> StopReason {
+        let mut watchdog = Watchdog::new(self.program, &self.machine, None);
+        loop {
+            if let Some(outcome) = watchdog.check(self.machine.pc) {
+                return StopReason::Ended(outcome);
+            }
+            let step = self.step();
+            if let Some(outcome) = self.outcome {
+                return StopReason::Ended(outcome);
+            }
+            let step = step.expect("a step that didn't end the program");
+            if self.watch_acc && step.acc_before != step.acc_after {
+                return StopReason::AccChanged {
+                    from: step.acc_before,
+                    to: step.acc_after,
+                };
+            }
+            if let Some(breakpoint) = self.breakpoint_at(self.machine.pc) {
+                return StopReason::Breakpoint(breakpoint);
+            }
+        }
+    }
+
+    pub fn write_trace(&self, output: &mut impl Write) -> io::Result<()> {
+        writeln!(output, "pc\tinstruction\tacc_before\tacc_after")?;
+        for step in self.trace.iter() {
+            writeln!(
+                output,
+                "{}\t{}\t{}\t{}",
+                step.pc, step.ins, step.acc_before, step.acc_after
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn dump_trace(&self, path: &str) -> io::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        self.write_trace(&mut file)?;
+        file.flush()
+    }
+}
+
+const HELP: &str = "\
+step [n]        execute n instructions (default 1)
+continue        run to the next breakpoint, acc change, loop or halt
+break <pc>      break before executing pc
+break <op>      break before executing any <op> instruction
+clear           remove all breakpoints
+watch           toggle stopping whenever acc changes
+back [n]        undo n steps (default 1)
+history         print remembered steps
+trace <file>    write the full execution trace to a file
+print           print machine state
+quit";
+
+fn print_step(output: &mut impl Write, step: &Step) -> io::Result<()> {
+    writeln!(
+        output,
+        "{:>5}  {:<8} acc {} -> {}",
+        step.pc, step.ins, step.acc_before, step.acc_after
+    )
+}
+
+fn print_state(output: &mut impl Write, debugger: &Debugger) -> io::Result<()> {
+    match debugger.current() {
+        Some(ins) => writeln!(
+            output,
+            "pc {} acc {} next {}",
+            debugger.machine.pc, debugger.machine.acc, ins
+        ),
+        None => writeln!(
+            output,
+            "pc {} acc {} halted",
+            debugger.machine.pc, debugger.machine.acc
+        ),
+    }?;
+    match debugger.outcome() {
+        Some(outcome) => writeln!(output, "ended: {:?}", outcome),
+        None => Ok(()),
+    }
+}
+
+pub fn repl(program: &[Ins], input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+    let mut debugger = Debugger::new(program, 100);
+    print_state(&mut output, &debugger)?;
+    for line in input.lines() {
+        let line = line?;
+        let mut words = line.split_whitespace();
+        let count = |arg: Option<&str>| arg.and_then(|n| n.parse().ok()).unwrap_or(1);
+        match words.next() {
+            Some("step") | Some("s") => {
+                for _ in 0..count(words.next()) {
+                    match debugger.step() {
+                        Some(step) => print_step(&mut output, &step)?,
+                        None => break,
+                    }
+                }
+            }
+            Some("continue") | Some("c") => {
+                let reason = debugger.run();
+                writeln!(output, "stopped: {:?}", reason)?;
+            }
+            Some("break") | Some("b") => match words.next() {
+                Some(arg) => {
+                    let breakpoint = match (arg.parse(), Opcode::from_mnemonic(arg)) {
+                        (Ok(pc), _) => Breakpoint::Pc(pc),
+                        (_, Some(op)) => Breakpoint::Opcode(op),
+                        _ => {
+                            writeln!(output, "unknown breakpoint {}", arg)?;
+                            continue;
+                        }
+                    };
+                    debugger.add_breakpoint(breakpoint);
+                }
+                None => writeln!(output, "break needs a pc or an opcode")?,
+            },
+            Some("clear") => debugger.clear_breakpoints(),
+            Some("watch") | Some("w") => {
+                let watch = !debugger.watch_acc;
+                debugger.watch_acc(watch);
+                writeln!(output, "watching acc: {}", watch)?;
+            }
+            Some("back") => {
+                for _ in 0..count(words.next()) {
+                    if debugger.back().is_none() {
+                        writeln!(output, "no more history")?;
+                        break;
+                    }
+                }
+            }
+            Some("history") | Some("h") => {
+                for step in debugger.history() {
+                    print_step(&mut output, step)?;
+                }
+            }
+            Some("trace") => match words.next() {
+                Some(path) => {
+                    if let Err(err) = debugger.dump_trace(path) {
+                        writeln!(output, "cannot write {}: {}", path, err)?;
+                    }
+                }
+                None => writeln!(output, "trace needs a file name")?,
+            },
+            Some("print") | Some("p") => {}
+            Some("quit") | Some("q") => break,
+            Some(_) => writeln!(output, "{}", HELP)?,
+            None => continue,
+        }
+        print_state(&mut output, &debugger)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn debugger_test() {
+    use crate::asm::assemble;
+
+    let program =
+        assemble("nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6")
+            .unwrap();
+    let mut debugger = Debugger::new(&program, 2);
+    debugger.add_breakpoint(Breakpoint::Opcode(Opcode::Jmp));
+    assert_eq!(
+        debugger.run(),
+        StopReason::Breakpoint(Breakpoint::Opcode(Opcode::Jmp))
+    );
+    assert_eq!((debugger.machine.pc, debugger.machine.acc), (2, 1));
+
+    debugger.clear_breakpoints();
+    debugger.watch_acc(true);
+    assert_eq!(debugger.run(), StopReason::AccChanged { from: 1, to: 2 });
+    assert_eq!(debugger.back().unwrap().pc, 6);
+    assert_eq!(debugger.back().unwrap().pc, 2);
+    assert_eq!(debugger.back(), None);
+    assert_eq!((debugger.machine.pc, debugger.machine.acc), (2, 1));
+
+    debugger.watch_acc(false);
+    assert_eq!(debugger.run(), StopReason::Ended(RunOutcome::Loop(2)));
+
+    let mut trace = vec![];
+    debugger.write_trace(&mut trace).unwrap();
+    let trace = String::from_utf8(trace).unwrap();
+    assert!(trace.starts_with("pc\tinstruction\tacc_before\tacc_after\n0\tnop +0\t0\t0\n"));
+
+    let program = assemble("acc +1\npop").unwrap();
+    let mut debugger = Debugger::new(&program, 2);
+    assert_eq!(
+        debugger.run(),
+        StopReason::Ended(RunOutcome::UnknownInstruction {
+            pc: 1,
+            ins: Ins::new(Opcode::Pop, 0)
+        })
+    );
+    assert_eq!((debugger.machine.pc, debugger.machine.acc), (1, 1));
+    assert!(debugger.current().is_none() || debugger.outcome().is_some());
+
+    // continue and run agree on how a program ends
+    for source in ["acc +1\njmp +5", "acc +1\njmp +1", "acc +1\nnop +0"].iter() {
+        let program = assemble(source).unwrap();
+        let mut debugger = Debugger::new(&program, 2);
+        let outcome = crate::run::run(&program, &mut Machine::default(), None);
+        assert_eq!(debugger.run(), StopReason::Ended(outcome));
+        assert!(debugger.current().is_none() || debugger.outcome().is_some());
+    }
+
+    // revisits aren't loops on an extended machine, the input runs out instead
+    let program = assemble("in\nout\njmp -2").unwrap();
+    let mut debugger = Debugger::new(&program, 2);
+    debugger.machine = Machine::extended(0, vec![1, 2]);
+    assert_eq!(
+        debugger.run(),
+        StopReason::Ended(RunOutcome::Fault {
+            pc: 0,
+            fault: crate::machine::Fault::InputExhausted
+        })
+    );
+}
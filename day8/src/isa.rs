@@ -0,0 +1,261 @@
+use std::fmt;
+
+use crate::machine::{Fault, Machine, REGISTERS};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Opcode {
+    Nop,
+    Acc,
+    Jmp,
+    // extended mode only
+    Ld,
+    St,
+    Add,
+    Ldm,
+    Stm,
+    Push,
+    Pop,
+    In,
+    Out,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operand {
+    Immediate,
+    Register,
+    Address,
+    None,
+}
+
+pub struct OpcodeInfo {
+    pub opcode: Opcode,
+    pub mnemonic: &'static str,
+    pub operand: Operand,
+    pub exec: fn(&mut Machine, i64) -> Result<(), Fault>,
+}
+
+fn exec_nop(machine: &mut Machine, _: i64) -> Result<(), Fault> {
+    machine.pc += 1;
+    Ok(())
+}
+
+fn exec_acc(machine: &mut Machine, value: i64) -> Result<(), Fault> {
+    machine.acc = machine.acc.checked_add(value).ok_or(Fault::Overflow)?;
+    machine.pc += 1;
+    Ok(())
+}
+
+fn exec_jmp(machine: &mut Machine, offset: i64) -> Result<(), Fault> {
+    let target = (machine.pc as i64)
+        .checked_add(offset)
+        .ok_or(Fault::JumpOverflow(offset))?;
+    if target < 0 {
+        return Err(Fault::NegativeJump(target));
+    }
+    machine.pc = target as usize;
+    Ok(())
+}
+
+// extended instructions move values between acc and the rest of the machine,
+// they check everything before touching state so a fault leaves it intact
+
+fn exec_ld(machine: &mut Machine, register: i64) -> Result<(), Fault> {
+    machine.acc = *machine.register(register)?;
+    machine.pc += 1;
+    Ok(())
+}
+
+fn exec_st(machine: &mut Machine, register: i64) -> Result<(), Fault> {
+    *machine.register(register)? = machine.acc;
+    machine.pc += 1;
+    Ok(())
+}
+
+fn exec_add(machine: &mut Machine, register: i64) -> Result<(), Fault> {
+    let value = *machine.register(register)?;
+    machine.acc = machine.acc.checked_add(value).ok_or(Fault::Overflow)?;
+    machine.pc += 1;
+    Ok(())
+}
+
+fn exec_ldm(machine: &mut Machine, address: i64) -> Result<(), Fault> {
+    machine.acc = *machine.memory(address)?;
+    machine.pc += 1;
+    Ok(())
+}
+
+fn exec_stm(machine: &mut Machine, address: i64) -> Result<(), Fault> {
+    *machine.memory(address)? = machine.acc;
+    machine.pc += 1;
+    Ok(())
+}
+
+fn exec_push(machine: &mut Machine, _: i64) -> Result<(), Fault> {
+    let acc = machine.acc;
+    machine.extension()?.stack.push(acc);
+    machine.pc += 1;
+    Ok(())
+}
+
+fn exec_pop(machine: &mut Machine, _: i64) -> Result<(), Fault> {
+    machine.acc = machine
+        .extension()?
+        .stack
+        .pop()
+        .ok_or(Fault::StackUnderflow)?;
+    machine.pc += 1;
+    Ok(())
+}
+
+fn exec_in(machine: &mut Machine, _: i64) -> Result<(), Fault> {
+    machine.acc = machine
+        .extension()?
+        .input
+        .pop_front()
+        .ok_or(Fault::InputExhausted)?;
+    machine.pc += 1;
+    Ok(())
+}
+
+fn exec_out(machine: &mut Machine, _: i64) -> Result<(), Fault> {
+    let acc = machine.acc;
+    machine.extension()?.output.push(acc);
+    machine.pc += 1;
+    Ok(())
+}
+
+// adding an opcode only needs a new variant and an entry here,
+// `Machine::exec` looks instructions up in this table
+pub const INSTRUCTION_SET: &[OpcodeInfo] = &[
+    OpcodeInfo {
+        opcode: Opcode::Nop,
+        mnemonic: "nop",
+        operand: Operand::Immediate,
+        exec: exec_nop,
+    },
+    OpcodeInfo {
+        opcode: Opcode::Acc,
+        mnemonic: "acc",
+        operand: Operand::Immediate,
+        exec: exec_acc,
+    },
+    OpcodeInfo {
+        opcode: Opcode::Jmp,
+        mnemonic: "jmp",
+        operand: Operand::Immediate,
+        exec: exec_jmp,
+    },
+    OpcodeInfo {
+        opcode: Opcode::Ld,
+        mnemonic: "ld",
+        operand: Operand::Register,
+        exec: exec_ld,
+    },
+    OpcodeInfo {
+        opcode: Opcode::St,
+        mnemonic: "st",
+        operand: Operand::Register,
+        exec: exec_st,
+    },
+    OpcodeInfo {
+        opcode: Opcode::Add,
+        mnemonic: "add",
+        operand: Operand::Register,
+        exec: exec_add,
+    },
+    OpcodeInfo {
+        opcode: Opcode::Ldm,
+        mnemonic: "ldm",
+        operand: Operand::Address,
+        exec: exec_ldm,
+    },
+    OpcodeInfo {
+        opcode: Opcode::Stm,
+        mnemonic: "stm",
+        operand: Operand::Address,
+        exec: exec_stm,
+    },
+    OpcodeInfo {
+        opcode: Opcode::Push,
+        mnemonic: "push",
+        operand: Operand::None,
+        exec: exec_push,
+    },
+    OpcodeInfo {
+        opcode: Opcode::Pop,
+        mnemonic: "pop",
+        operand: Operand::None,
+        exec: exec_pop,
+    },
+    OpcodeInfo {
+        opcode: Opcode::In,
+        mnemonic: "in",
+        operand: Operand::None,
+        exec: exec_in,
+    },
+    OpcodeInfo {
+        opcode: Opcode::Out,
+        mnemonic: "out",
+        operand: Operand::None,
+        exec: exec_out,
+    },
+];
+
+impl Opcode {
+    pub fn info(self) -> &'static OpcodeInfo {
+        INSTRUCTION_SET
+            .iter()
+            .find(|info| info.opcode == self)
+            .expect("every opcode is in the instruction set")
+    }
+
+    pub fn from_mnemonic(mnemonic: &str) -> Option<Opcode> {
+        INSTRUCTION_SET
+            .iter()
+            .find(|info| info.mnemonic == mnemonic)
+            .map(|info| info.opcode)
+    }
+
+    pub fn mnemonic(self) -> &'static str {
+        self.info().mnemonic
+    }
+
+    pub fn operand(self) -> Operand {
+        self.info().operand
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ins {
+    pub op: Opcode,
+    pub arg: i64,
+}
+
+impl Ins {
+    pub fn new(op: Opcode, arg: i64) -> Self {
+        Ins { op, arg }
+    }
+
+    pub fn toggle(&mut self) {
+        match self.op {
+            Opcode::Jmp => self.op = Opcode::Nop,
+            Opcode::Nop => self.op = Opcode::Jmp,
+            _ => {}
+        }
+    }
+}
+
+impl fmt::Display for Ins {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mnemonic = self.op.mnemonic();
+        match self.op.operand() {
+            Operand::Immediate => write!(f, "{} {:+}", mnemonic, self.arg),
+            Operand::Register => match REGISTERS.get(self.arg as usize) {
+                Some(name) => write!(f, "{} {}", mnemonic, name),
+                None => write!(f, "{} r{}", mnemonic, self.arg),
+            },
+            Operand::Address => write!(f, "{} {}", mnemonic, self.arg),
+            Operand::None => write!(f, "{}", mnemonic),
+        }
+    }
+}
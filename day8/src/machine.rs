@@ -0,0 +1,117 @@
+use std::{collections::VecDeque, convert::TryFrom, fmt};
+
+use crate::isa::Ins;
+
+pub const REGISTERS: [&str; 8] = ["a", "b", "c", "d", "e", "f", "g", "h"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Fault {
+    // an extended instruction ran on a plain machine
+    NotExtended,
+    // pc can't go below 0, the machine stays on the jump
+    NegativeJump(i64),
+    // the jump offset takes pc past i64, the machine stays on the jump
+    JumpOverflow(i64),
+    // acc would overflow, it keeps its old value
+    Overflow,
+    BadRegister(i64),
+    BadAddress(i64),
+    StackUnderflow,
+    InputExhausted,
+}
+
+impl fmt::Display for Fault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Fault::NotExtended => write!(f, "instruction needs an extended machine"),
+            Fault::NegativeJump(target) => write!(f, "jump to negative pc {}", target),
+            Fault::JumpOverflow(offset) => write!(f, "jump by {} overflows pc", offset),
+            Fault::Overflow => write!(f, "acc overflows"),
+            Fault::BadRegister(register) => write!(f, "no register {}", register),
+            Fault::BadAddress(address) => write!(f, "address {} out of memory", address),
+            Fault::StackUnderflow => write!(f, "pop from an empty stack"),
+            Fault::InputExhausted => write!(f, "input queue is empty"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Extension {
+    pub registers: [i64; REGISTERS.len()],
+    pub memory: Vec<i64>,
+    pub stack: Vec<i64>,
+    pub input: VecDeque<i64>,
+    pub output: Vec<i64>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Machine {
+    pub acc: i64,
+    pub pc: usize,
+    // registers, memory, stack and I/O, absent for the original programs
+    pub ext: Option<Box<Extension>>,
+}
+
+impl Machine {
+    pub fn extended(memory_size: usize, input: impl IntoIterator<Item = i64>) -> Self {
+        Machine {
+            ext: Some(Box::new(Extension {
+                memory: vec![0; memory_size],
+                input: input.into_iter().collect(),
+                ..Extension::default()
+            })),
+            ..Machine::default()
+        }
+    }
+
+    pub fn exec(&mut self, instruction: &Ins) -> Result<(), Fault> {
+        (instruction.op.info().exec)(self, instruction.arg)
+    }
+
+    pub fn extension(&mut self) -> Result<&mut Extension, Fault> {
+        self.ext.as_deref_mut().ok_or(Fault::NotExtended)
+    }
+
+    pub fn register(&mut self, register: i64) -> Result<&mut i64, Fault> {
+        let ext = self.extension()?;
+        usize::try_from(register)
+            .ok()
+            .and_then(move |idx| ext.registers.get_mut(idx))
+            .ok_or(Fault::BadRegister(register))
+    }
+
+    pub fn memory(&mut self, address: i64) -> Result<&mut i64, Fault> {
+        let ext = self.extension()?;
+        usize::try_from(address)
+            .ok()
+            .and_then(move |idx| ext.memory.get_mut(idx))
+            .ok_or(Fault::BadAddress(address))
+    }
+
+    pub fn output(&self) -> &[i64] {
+        self.ext.as_ref().map_or(&[], |ext| &ext.output)
+    }
+}
+
+#[test]
+fn extended_machine_test() {
+    use crate::asm::assemble;
+
+    // sums the input and echoes it reversed through the stack
+    let program = assemble(
+        "in\nst a\nadd b\nst b\nld a\npush\nin\nst a\nadd b\nst b\nld a\npush\n\
+         ld b\nstm 0\npop\nout\npop\nout\nldm 0\nout",
+    )
+    .unwrap();
+    let mut machine = Machine::extended(1, vec![3, 4]);
+    while let Some(ins) = program.get(machine.pc) {
+        machine.exec(ins).unwrap();
+    }
+    assert_eq!(machine.output(), &[4, 3, 7]);
+
+    let mut plain = Machine::default();
+    assert_eq!(plain.exec(&program[0]), Err(Fault::NotExtended));
+    assert_eq!(plain.pc, 0);
+    assert_eq!(machine.exec(&program[14]), Err(Fault::StackUnderflow));
+    assert_eq!(machine.exec(&program[0]), Err(Fault::InputExhausted));
+}
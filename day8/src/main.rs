@@ -1,96 +1,82 @@
-use itertools::Itertools;
-use std::fs::read_to_string;
+use std::{env, fs::read_to_string, io};
 
-#[derive(Debug, Clone)]
-struct Ins<'a>(&'a str, i64);
+use analysis::analyse;
+use asm::{assemble, disassemble};
+use isa::Ins;
+use machine::Machine;
+use repair::repair;
+use run::{run, RunOutcome};
 
-impl<'a> Ins<'a> {
-    fn toggle(&mut self) {
-        match self.0 {
-            "jmp" => self.0 = "nop",
-            "nop" => self.0 = "jmp",
-            _ => {}
-        }
+mod analysis;
+mod asm;
+mod cfg;
+mod debugger;
+mod isa;
+mod machine;
+mod repair;
+mod run;
+
+fn part_1(instruction_list: &[Ins]) -> i64 {
+    let mut machine = Machine::default();
+    match run(instruction_list, &mut machine, None) {
+        RunOutcome::Loop(_) => machine.acc,
+        outcome => panic!("expected a loop, got {:?}", outcome),
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-struct Machine {
-    acc: i64,
-    pc: usize,
+fn part_2(instruction_list: &[Ins]) -> i64 {
+    repair(instruction_list)
+        .expect("program can be repaired with a single flip")
+        .acc
 }
 
-impl Default for Machine {
-    fn default() -> Self {
-        Machine { acc: 0, pc: 0 }
+// runs any program on an extended machine, feeding it numbers from the
+// command line and printing whatever it outputs
+fn run_extended(path: &str, input: Vec<i64>) {
+    let source = read_to_string(path).unwrap_or_else(|err| panic!("{}: {}", path, err));
+    let program = assemble(&source).unwrap_or_else(|err| panic!("{}", err));
+    let mut machine = Machine::extended(256, input);
+    let outcome = run(&program, &mut machine, None);
+    if !outcome.terminated() {
+        println!("stopped: {:?}", outcome);
     }
-}
-
-impl Machine {
-    fn exec(&mut self, instruction: &Ins) {
-        match instruction {
-            Ins("nop", _) => {
-                self.pc += 1;
-            }
-            Ins("acc", value) => {
-                self.acc += value;
-                self.pc += 1;
-            }
-            Ins("jmp", offset) => {
-                self.pc = ((self.pc as i64) + offset) as usize;
-            }
-            Ins(op, arg) => panic!("Unknown instruction ({}, {})", op, arg),
-        }
+    for value in machine.output() {
+        println!("{}", value);
     }
 }
 
-fn exec_to_halt(instruction_list: Vec<Ins>) -> Result<Machine, Machine> {
-    let mut visited: Vec<usize> = vec![];
-    let mut machine = Machine::default();
-    loop {
-        if visited.contains(&machine.pc) {
-            // loop detected, won't halt
-            return Result::Err(machine);
+fn main() {
+    if env::args().nth(1).as_deref() == Some("run") {
+        let path = env::args().nth(2).expect("run needs a program file");
+        let input = env::args()
+            .skip(3)
+            .map(|arg| {
+                arg.parse()
+                    .unwrap_or_else(|_| panic!("invalid input {}", arg))
+            })
+            .collect();
+        run_extended(&path, input);
+        return;
+    }
+    let input = read_to_string("./src/input").unwrap();
+    let instruction_list = assemble(&input).unwrap_or_else(|err| panic!("{}", err));
+    match env::args().nth(1).as_deref() {
+        Some("debug") => {
+            let stdin = io::stdin();
+            debugger::repl(&instruction_list, stdin.lock(), io::stdout()).unwrap();
+        }
+        Some("analyse") => {
+            let analysis = analyse(&instruction_list);
+            println!("unreachable: {:?}", analysis.unreachable);
+            println!("out of bounds jumps: {:?}", analysis.out_of_bounds);
+            println!("certain loops: {:?}", analysis.certain_loops);
+            println!("possible loops: {:?}", analysis.possible_loops);
         }
-        visited.push(machine.pc);
-        match instruction_list.get(machine.pc) {
-            Some(ins) => machine.exec(ins),
-            // halted
-            None => return Result::Ok(machine),
+        Some("disasm") => print!("{}", disassemble(&instruction_list)),
+        Some("dot") => print!("{}", analyse(&instruction_list).to_dot(&instruction_list)),
+        _ => {
+            println!("Part 1: {}", part_1(&instruction_list));
+            println!("Part 2: {}", part_2(&instruction_list));
         }
     }
 }
-
-fn part_1(instruction_list: &Vec<Ins>) -> i64 {
-    exec_to_halt(instruction_list.clone()).unwrap_err().acc
-}
-
-fn part_2(instruction_list: &Vec<Ins>) -> i64 {
-    instruction_list
-        .iter()
-        .enumerate()
-        .filter(|(_, Ins(op, _))| *op == "jmp" || *op == "nop")
-        .map(|(idx_to_change, _)| {
-            let mut to_modify = instruction_list.clone();
-            to_modify[idx_to_change].toggle();
-            to_modify
-        })
-        .map(exec_to_halt)
-        .filter(|machine| machine.is_ok())
-        .map(|machine| machine.unwrap().acc)
-        .exactly_one()
-        .unwrap()
-}
-
-fn parse_line(input: &str) -> Ins {
-    let (op, arg) = input.split(' ').collect_tuple().unwrap();
-    let arg = arg.parse::<i64>().unwrap();
-    Ins(op, arg)
-}
-
-fn main() {
-    let input = read_to_string("./src/input").unwrap();
-    let instruction_list: Vec<Ins> = input.lines().map(|line| parse_line(line)).collect();
-    println!("Part 1: {}", part_1(&instruction_list));
-    println!("Part 2: {}", part_2(&instruction_list));
-}
@@ -0,0 +1,69 @@
+use crate::{
+    cfg::{successor, Cfg},
+    isa::{Ins, Opcode},
+    machine::Machine,
+    run::run,
+};
+
+#[derive(Debug, PartialEq)]
+pub struct Repair {
+    pub index: usize,
+    pub original: Opcode,
+    pub replacement: Opcode,
+    pub acc: i64,
+}
+
+// the only instructions worth flipping are the ones executed by the broken
+// program, and a flip fixes it when the flipped successor is known to reach
+// the end, so one backward search plus one walk is enough
+pub fn repair(program: &[Ins]) -> Option<Repair> {
+    let cfg = Cfg::new(program);
+    let terminating = cfg.terminating();
+    if terminating[0] {
+        // nothing to repair
+        return None;
+    }
+    let reaches_end =
+        |next: i64| next >= 0 && next as usize <= cfg.end() && terminating[next as usize];
+    let index = cfg.path_from_start().into_iter().find(|&pc| {
+        let mut flipped = program[pc];
+        flipped.toggle();
+        flipped.op != program[pc].op && reaches_end(successor(pc, &flipped))
+    })?;
+
+    let mut repaired = program.to_vec();
+    repaired[index].toggle();
+    let mut machine = Machine::default();
+    if !run(&repaired, &mut machine, None).terminated() {
+        return None;
+    }
+    Some(Repair {
+        index,
+        original: program[index].op,
+        replacement: repaired[index].op,
+        acc: machine.acc,
+    })
+}
+
+#[test]
+fn repair_test() {
+    use crate::asm::assemble;
+
+    let program =
+        assemble("nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6")
+            .unwrap();
+    assert_eq!(
+        repair(&program),
+        Some(Repair {
+            index: 7,
+            original: Opcode::Jmp,
+            replacement: Opcode::Nop,
+            acc: 8
+        })
+    );
+    assert_eq!(repair(&assemble("jmp +0\nacc +1").unwrap()).unwrap().acc, 1);
+    assert_eq!(repair(&assemble("jmp +0\njmp -1").unwrap()), None);
+    assert_eq!(repair(&assemble("acc +1\nnop +5").unwrap()), None);
+    let far = assemble("jmp +0\njmp +9223372036854775807").unwrap();
+    assert_eq!(repair(&far), None);
+}
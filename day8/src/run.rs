@@ -0,0 +1,215 @@
+use crate::{
+    isa::{Ins, Opcode},
+    machine::{Fault, Machine},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RunOutcome {
+    // fell through the last instruction
+    Halted,
+    // a jump landed exactly one past the last instruction
+    JumpedToEnd,
+    JumpedBelowStart { pc: usize, target: i64 },
+    JumpedPastEnd { pc: usize, target: i64 },
+    // the instruction at this pc was already executed
+    Loop(usize),
+    StepLimit(usize),
+    // the machine has no support for this instruction
+    UnknownInstruction { pc: usize, ins: Ins },
+    Fault { pc: usize, fault: Fault },
+}
+
+impl RunOutcome {
+    pub fn terminated(self) -> bool {
+        matches!(self, RunOutcome::Halted | RunOutcome::JumpedToEnd)
+    }
+}
+
+// the instruction at pc, or how the run ended if pc is outside the program
+pub fn fetch(program: &[Ins], pc: usize) -> Result<Ins, RunOutcome> {
+    match program.get(pc) {
+        Some(&ins) => Ok(ins),
+        None if pc == program.len() => Err(RunOutcome::Halted),
+        // only reachable when starting out of bounds
+        None => Err(RunOutcome::JumpedPastEnd {
+            pc,
+            target: pc as i64,
+        }),
+    }
+}
+
+// how the run ended after executing `ins` at `pc`, None if it goes on
+pub fn executed(
+    program: &[Ins],
+    pc: usize,
+    ins: Ins,
+    result: Result<(), Fault>,
+    machine: &Machine,
+) -> Option<RunOutcome> {
+    match result {
+        Ok(()) => {}
+        Err(Fault::NegativeJump(target)) => {
+            return Some(RunOutcome::JumpedBelowStart { pc, target })
+        }
+        // the target doesn't fit in i64, so it saturates like in the cfg
+        Err(Fault::JumpOverflow(offset)) => {
+            let target = (pc as i64).saturating_add(offset);
+            return Some(if offset < 0 {
+                RunOutcome::JumpedBelowStart { pc, target }
+            } else {
+                RunOutcome::JumpedPastEnd { pc, target }
+            });
+        }
+        Err(Fault::NotExtended) => return Some(RunOutcome::UnknownInstruction { pc, ins }),
+        Err(fault) => return Some(RunOutcome::Fault { pc, fault }),
+    }
+    if machine.pc > program.len() {
+        return Some(RunOutcome::JumpedPastEnd {
+            pc,
+            target: machine.pc as i64,
+        });
+    }
+    if machine.pc == program.len() && ins.op == Opcode::Jmp {
+        return Some(RunOutcome::JumpedToEnd);
+    }
+    None
+}
+
+// step limit for extended machines when none is given
+pub const DEFAULT_STEP_LIMIT: usize = 1_000_000;
+
+// stops a run on a revisited instruction or after `step_limit` instructions.
+// jumps are unconditional, so a revisit is a certain loop, except on extended
+// machines where the loop may still end on an I/O or stack fault, those are
+// only stopped by the step limit, DEFAULT_STEP_LIMIT unless one is given
+pub struct Watchdog {
+    visited: Option<Vec<bool>>,
+    steps: usize,
+    step_limit: Option<usize>,
+}
+
+impl Watchdog {
+    pub fn new(program: &[Ins], machine: &Machine, step_limit: Option<usize>) -> Self {
+        Watchdog {
+            visited: machine.ext.is_none().then(|| vec![false; program.len()]),
+            steps: 0,
+            step_limit: step_limit.or_else(|| machine.ext.as_ref().map(|_| DEFAULT_STEP_LIMIT)),
+        }
+    }
+
+    // call before executing the instruction at pc
+    pub fn check(&mut self, pc: usize) -> Option<RunOutcome> {
+        if let Some(visited) = self
+            .visited
+            .as_mut()
+            .and_then(|visited| visited.get_mut(pc))
+        {
+            if *visited {
+                return Some(RunOutcome::Loop(pc));
+            }
+            *visited = true;
+        }
+        if self.step_limit == Some(self.steps) {
+            return Some(RunOutcome::StepLimit(self.steps));
+        }
+        self.steps += 1;
+        None
+    }
+}
+
+// runs until the program leaves its bounds, faults or the watchdog stops it
+pub fn run(program: &[Ins], machine: &mut Machine, step_limit: Option<usize>) -> RunOutcome {
+    let mut watchdog = Watchdog::new(program, machine, step_limit);
+    loop {
+        let pc = machine.pc;
+        let ins = match fetch(program, pc) {
+            Ok(ins) => ins,
+            Err(outcome) => return outcome,
+        };
+        if let Some(outcome) = watchdog.check(pc) {
+            return outcome;
+        }
+        let result = machine.exec(&ins);
+        if let Some(outcome) = executed(program, pc, ins, result, machine) {
+            return outcome;
+        }
+    }
+}
+
+#[test]
+fn run_test() {
+    use crate::asm::assemble;
+
+    let outcome = |source: &str, step_limit| {
+        let mut machine = Machine::default();
+        let outcome = run(&assemble(source).unwrap(), &mut machine, step_limit);
+        (outcome, machine.acc)
+    };
+    assert_eq!(outcome("acc +1\nnop +0", None), (RunOutcome::Halted, 1));
+    assert_eq!(
+        outcome("acc +1\njmp +1", None),
+        (RunOutcome::JumpedToEnd, 1)
+    );
+    assert_eq!(
+        outcome("acc +1\njmp -2", None),
+        (RunOutcome::JumpedBelowStart { pc: 1, target: -1 }, 1)
+    );
+    assert_eq!(
+        outcome("jmp +3\nacc +1", None),
+        (RunOutcome::JumpedPastEnd { pc: 0, target: 3 }, 0)
+    );
+    assert_eq!(
+        outcome("acc +9223372036854775807\nacc +1", None),
+        (
+            RunOutcome::Fault {
+                pc: 1,
+                fault: Fault::Overflow
+            },
+            i64::MAX
+        )
+    );
+    assert_eq!(
+        outcome("nop +0\njmp +9223372036854775807", None),
+        (
+            RunOutcome::JumpedPastEnd {
+                pc: 1,
+                target: i64::MAX
+            },
+            0
+        )
+    );
+    let looping = "nop +0\nacc +2\njmp -1";
+    assert_eq!(outcome(looping, None), (RunOutcome::Loop(1), 2));
+    assert_eq!(outcome(looping, Some(2)), (RunOutcome::StepLimit(2), 2));
+    assert_eq!(
+        outcome("acc +1\nout", None),
+        (
+            RunOutcome::UnknownInstruction {
+                pc: 1,
+                ins: Ins::new(Opcode::Out, 0)
+            },
+            1
+        )
+    );
+
+    // echoes input until it runs out, only the fault stops it
+    let program = assemble("in\nout\njmp -2").unwrap();
+    let mut machine = Machine::extended(0, vec![1, 2]);
+    assert_eq!(
+        run(&program, &mut machine, Some(100)),
+        RunOutcome::Fault {
+            pc: 0,
+            fault: Fault::InputExhausted
+        }
+    );
+    assert_eq!(machine.output(), &[1, 2]);
+    let mut machine = Machine::extended(0, vec![]);
+    assert_eq!(
+        run(&assemble("jmp +0").unwrap(), &mut machine, Some(10)),
+        RunOutcome::StepLimit(10)
+    );
+    assert_eq!(
+        run(&assemble("jmp +0").unwrap(), &mut machine, None),
+        RunOutcome::StepLimit(DEFAULT_STEP_LIMIT)
+    );
+}
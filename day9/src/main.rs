@@ -1,62 +1,81 @@
-use itertools::Itertools;
-use std::fs::read_to_string;
+use std::{env, fmt::Display, fs::read_to_string, str::FromStr};
 
-fn check_if_sums_to(numbers: &Vec<usize>, target_value: usize) -> Option<Vec<usize>> {
-    let mut sum = 0;
-    let mut set = vec![];
-    for num in numbers.iter() {
-        sum += num;
-        set.push(*num);
-        if sum > target_value {
-            return None;
-        } else if sum == target_value {
-            return Some(set);
-        }
-    }
-    return None;
+use num_bigint::BigInt;
+use ranges::{prefix_sum, two_pointer};
+use validator::invalid_numbers;
+use value::Value;
+
+mod ranges;
+mod validator;
+mod value;
+
+fn parse<T: FromStr>(input: &str) -> impl Iterator<Item = T> + '_ {
+    input.lines().enumerate().map(|(idx, line)| {
+        line.trim()
+            .parse()
+            .unwrap_or_else(|_| panic!("line {}: invalid number {}", idx + 1, line))
+    })
 }
 
-fn part_1(numbers: &Vec<usize>, preamble_size: usize) -> usize {
-    numbers
-        .iter()
-        .enumerate()
-        .skip(preamble_size)
-        .find(|&(idx, &number)| {
-            numbers.clone().as_slice()[idx - preamble_size..idx]
-                .iter()
-                .tuple_combinations::<(_, _)>()
-                .all(|(a, b)| a + b != number)
-        })
-        .map(|(_, &number)| number)
-        .unwrap()
+fn part_1<T: Value>(numbers: &[T], preamble_size: usize) -> T {
+    invalid_numbers(numbers.iter().cloned(), preamble_size)
+        .next()
+        .expect("some number is invalid")
+        .value
 }
 
-fn part_2(numbers: &Vec<usize>, preamble_size: usize) -> usize {
-    let invalid_number = part_1(&numbers, preamble_size);
-    let target_set = numbers
-        .iter()
-        .enumerate()
-        .map(|(idx, _)| {
-            let rest: Vec<usize> = numbers.clone().as_slice()[idx..(numbers.len())]
-                .iter()
-                .map(|&a| a)
-                .collect();
-            check_if_sums_to(&rest, invalid_number)
-        })
-        .find(|opt| opt.is_some())
-        .unwrap()
-        .unwrap();
-    let smallest_number = target_set.clone().into_iter().min().unwrap();
-    let biggest_number = target_set.clone().into_iter().max().unwrap();
-    smallest_number + biggest_number
+fn part_2<T: Value>(numbers: &[T], preamble_size: usize) -> T {
+    let invalid_number = part_1(numbers, preamble_size);
+    let ranges = if numbers.iter().all(|n| n >= &T::zero()) {
+        two_pointer(numbers, &invalid_number)
+    } else {
+        prefix_sum(numbers, &invalid_number)
+    };
+    ranges
+        .unwrap_or_else(|err| panic!("{}", err))
+        .into_iter()
+        .next()
+        .expect("some range sums to the invalid number")
+        .weakness
+}
+
+fn analyse<T: Value + FromStr + Display>(input: &str, args: &[String]) {
+    let window = || {
+        args.get(1)
+            .map_or(25, |arg| arg.parse().expect("window size"))
+    };
+    match args.first().map(String::as_str) {
+        Some("invalid") => {
+            for invalid in invalid_numbers(parse::<T>(input), window()) {
+                println!("{}: {}", invalid.index, invalid.value);
+            }
+        }
+        Some("ranges") => {
+            let numbers: Vec<T> = parse(input).collect();
+            let target = part_1(&numbers, window());
+            for range in prefix_sum(&numbers, &target).unwrap_or_else(|err| panic!("{}", err)) {
+                println!(
+                    "{}..{}: min {} max {} weakness {}",
+                    range.start, range.end, range.min, range.max, range.weakness
+                );
+            }
+        }
+        _ => {
+            let numbers: Vec<T> = parse(input).collect();
+            println!("Part 1: {}", part_1(&numbers, 25));
+            println!("Part 2: {}", part_2(&numbers, 25));
+        }
+    }
 }
 
 fn main() {
     let input = read_to_string("./src/input").unwrap();
-    let numbers: Vec<usize> = input
-        .lines()
-        .map(|value| value.parse::<usize>().unwrap())
-        .collect();
-    println!("Part 1: {}", part_1(&numbers, 25));
-    println!("Part 2: {}", part_2(&numbers, 25));
+    // i64 unless --bigint is given anywhere
+    let (flags, args): (Vec<String>, Vec<String>) =
+        env::args().skip(1).partition(|arg| arg == "--bigint");
+    if flags.is_empty() {
+        analyse::<i64>(&input, &args);
+    } else {
+        analyse::<BigInt>(&input, &args);
+    }
 }
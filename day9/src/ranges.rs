@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+
+use crate::value::{Overflow, Value};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Range<T> {
+    pub start: usize,
+    // exclusive
+    pub end: usize,
+    pub min: T,
+    pub max: T,
+    // min + max
+    pub weakness: T,
+}
+
+impl<T: Value> Range<T> {
+    fn new(numbers: &[T], start: usize, end: usize) -> Result<Self, Overflow> {
+        let slice = &numbers[start..end];
+        let min = slice.iter().min().unwrap().clone();
+        let max = slice.iter().max().unwrap().clone();
+        let weakness = min.checked_add(&max).ok_or(Overflow { index: start })?;
+        Ok(Range {
+            start,
+            end,
+            min,
+            max,
+            weakness,
+        })
+    }
+}
+
+// sliding window, only correct when no number is negative. for each end the
+// matching starts are the leftmost one plus any run of zeros after it.
+// the window shrinks before a number is added, so the sum never passes the
+// target by more than a single number and can't overflow
+pub fn two_pointer<T: Value>(numbers: &[T], target: &T) -> Result<Vec<Range<T>>, Overflow> {
+    debug_assert!(numbers.iter().all(|n| n >= &T::zero()));
+    let mut ranges = vec![];
+    let mut start = 0;
+    let mut sum = T::zero();
+    for (end, number) in numbers.iter().enumerate() {
+        let mut next = sum.checked_add(number);
+        while start < end && next.as_ref().is_none_or(|next| next > target) {
+            sum = sum
+                .checked_sub(&numbers[start])
+                .expect("numbers are non-negative");
+            start += 1;
+            next = sum.checked_add(number);
+        }
+        sum = next.ok_or(Overflow { index: end })?;
+        if &sum != target {
+            continue;
+        }
+        let mut first = start;
+        while first < end {
+            ranges.push(Range::new(numbers, first, end + 1)?);
+            if !numbers[first].is_zero() {
+                break;
+            }
+            first += 1;
+        }
+    }
+    ranges.sort_by_key(|range| (range.start, range.end));
+    Ok(ranges)
+}
+
+// works for any signs, a range sums to target when two prefix sums differ by it
+pub fn prefix_sum<T: Value>(numbers: &[T], target: &T) -> Result<Vec<Range<T>>, Overflow> {
+    let mut seen: HashMap<T, Vec<usize>> = HashMap::new();
+    let mut ranges = vec![];
+    let mut prefix = T::zero();
+    seen.entry(T::zero()).or_default().push(0);
+    for (idx, number) in numbers.iter().enumerate() {
+        prefix = prefix.checked_add(number).ok_or(Overflow { index: idx })?;
+        let end = idx + 1;
+        // an unrepresentable difference can't match any earlier prefix
+        if let Some(starts) = prefix.checked_sub(target).and_then(|diff| seen.get(&diff)) {
+            for &start in starts.iter().filter(|&&start| end - start >= 2) {
+                ranges.push(Range::new(numbers, start, end)?);
+            }
+        }
+        seen.entry(prefix.clone()).or_default().push(end);
+    }
+    ranges.sort_by_key(|range| (range.start, range.end));
+    Ok(ranges)
+}
+
+#[test]
+fn ranges_test() {
+    use num_bigint::BigInt;
+
+    let numbers: Vec<i64> = vec![
+        35, 20, 15, 25, 47, 40, 62, 55, 65, 95, 102, 117, 150, 182, 127, 219, 299, 277, 309, 576,
+    ];
+    let expected = vec![Range {
+        start: 2,
+        end: 6,
+        min: 15,
+        max: 47,
+        weakness: 62,
+    }];
+    assert_eq!(two_pointer(&numbers, &127), Ok(expected.clone()));
+    assert_eq!(prefix_sum(&numbers, &127), Ok(expected));
+
+    let zeros: Vec<i64> = vec![0, 5, 0, 0, 5];
+    assert_eq!(two_pointer(&zeros, &5), prefix_sum(&zeros, &5));
+    assert_eq!(two_pointer(&zeros, &5).unwrap().len(), 7);
+
+    let signed: Vec<i64> = vec![3, -1, 4, -6, 5];
+    let bounds: Vec<_> = prefix_sum(&signed, &2)
+        .unwrap()
+        .iter()
+        .map(|range| (range.start, range.end))
+        .collect();
+    assert_eq!(bounds, vec![(0, 2), (1, 5)]);
+
+    let huge = vec![i64::MAX, 1, i64::MAX - 2, 2];
+    assert_eq!(prefix_sum(&huge, &3), Err(Overflow { index: 1 }));
+    let bounds: Vec<_> = two_pointer(&huge, &i64::MAX)
+        .unwrap()
+        .iter()
+        .map(|range| (range.start, range.end))
+        .collect();
+    assert_eq!(bounds, vec![(2, 4)]);
+    let huge: Vec<BigInt> = huge.into_iter().map(BigInt::from).collect();
+    let target = BigInt::from(i64::MAX) + 1;
+    let bounds: Vec<_> = prefix_sum(&huge, &target)
+        .unwrap()
+        .iter()
+        .map(|range| (range.start, range.end))
+        .collect();
+    assert_eq!(bounds, vec![(0, 2), (1, 4)]);
+}
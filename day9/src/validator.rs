@@ -0,0 +1,122 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::value::Value;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Invalid<T> {
+    pub index: usize,
+    pub value: T,
+}
+
+// keeps the last `window` numbers and a multiset of the sums of every pair of
+// them, so checking a number is one lookup and sliding costs O(window).
+// a pair whose sum overflows can't add up to any number, so it's left out
+pub struct Validator<T> {
+    window: usize,
+    recent: VecDeque<T>,
+    sums: HashMap<T, usize>,
+    index: usize,
+}
+
+impl<T: Value> Validator<T> {
+    pub fn new(window: usize) -> Self {
+        Validator {
+            window,
+            recent: VecDeque::with_capacity(window),
+            sums: HashMap::new(),
+            index: 0,
+        }
+    }
+
+    fn is_valid(&self, value: &T) -> bool {
+        // the preamble is valid by definition
+        self.recent.len() < self.window || self.sums.contains_key(value)
+    }
+
+    fn evict_oldest(&mut self) {
+        if let Some(oldest) = self.recent.pop_front() {
+            for other in self.recent.iter() {
+                if let Some(sum) = oldest.checked_add(other) {
+                    let count = self.sums.get_mut(&sum).unwrap();
+                    *count -= 1;
+                    if *count == 0 {
+                        self.sums.remove(&sum);
+                    }
+                }
+            }
+        }
+    }
+
+    // checks `value` against the window and then slides it in
+    pub fn push(&mut self, value: T) -> Option<Invalid<T>> {
+        let invalid = if self.is_valid(&value) {
+            None
+        } else {
+            Some(Invalid {
+                index: self.index,
+                value: value.clone(),
+            })
+        };
+        self.index += 1;
+        if self.window == 0 {
+            return invalid;
+        }
+        if self.recent.len() == self.window {
+            self.evict_oldest();
+        }
+        for other in self.recent.iter() {
+            if let Some(sum) = value.checked_add(other) {
+                *self.sums.entry(sum).or_default() += 1;
+            }
+        }
+        self.recent.push_back(value);
+        invalid
+    }
+}
+
+pub fn invalid_numbers<T: Value>(
+    numbers: impl IntoIterator<Item = T>,
+    window: usize,
+) -> impl Iterator<Item = Invalid<T>> {
+    let mut validator = Validator::new(window);
+    numbers
+        .into_iter()
+        .filter_map(move |value| validator.push(value))
+}
+
+#[test]
+fn validator_test() {
+    use num_bigint::BigInt;
+
+    let numbers: Vec<i64> = vec![
+        35, 20, 15, 25, 47, 40, 62, 55, 65, 95, 102, 117, 150, 182, 127, 219, 299, 277, 309, 576,
+    ];
+    assert_eq!(
+        invalid_numbers(numbers, 5).collect::<Vec<_>>(),
+        vec![Invalid {
+            index: 14,
+            value: 127
+        }]
+    );
+    // equal numbers at different positions still form a pair
+    assert_eq!(
+        invalid_numbers(vec![1, 1, 2, 3, 2, 9], 2).collect::<Vec<_>>(),
+        vec![
+            Invalid { index: 4, value: 2 },
+            Invalid { index: 5, value: 9 }
+        ]
+    );
+    assert_eq!(
+        invalid_numbers(vec![-4, 1, -3, i64::MAX, i64::MAX - 3, 5], 2)
+            .map(|invalid| invalid.index)
+            .collect::<Vec<_>>(),
+        vec![3, 5]
+    );
+    let big = |n: &str| n.parse::<BigInt>().unwrap();
+    let numbers = vec![
+        big("9223372036854775807"),
+        big("9223372036854775807"),
+        big("18446744073709551614"),
+    ];
+    assert!(invalid_numbers(numbers, 2).next().is_none());
+}
@@ -0,0 +1,24 @@
+use std::{fmt, hash::Hash};
+
+use num_traits::{CheckedAdd, CheckedSub, Zero};
+
+// anything the cipher can be analysed over, i64 and BigInt in practice
+pub trait Value: Clone + Ord + Hash + Zero + CheckedAdd + CheckedSub {}
+
+impl<T: Clone + Ord + Hash + Zero + CheckedAdd + CheckedSub> Value for T {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Overflow {
+    // index of the number whose addition overflowed
+    pub index: usize,
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "sum overflows at index {}, use big integers instead",
+            self.index
+        )
+    }
+}